@@ -102,6 +102,15 @@ impl Camera {
         }
     }
 
+    /// moves the camera straight to the player instead of easing
+    /// (for when the player suddenly ends up somewhere far away)
+    pub fn snap_to_player(&mut self, player: &RigidBody) {
+        self.bottom_left.x =
+            player.center.x - (MIN_X_FROM_CAMERA_BOTTOM_LEFT + MAX_X_FROM_CAMERA_BOTTOM_LEFT) / 2.0;
+        self.bottom_left.y =
+            player.center.y - (MIN_Y_FROM_CAMERA_BOTTOM_LEFT + MAX_Y_FROM_CAMERA_BOTTOM_LEFT) / 2.0;
+    }
+
     // renders the camera, using the inputted function to convert
     // pixels in the game world into rgb values
 
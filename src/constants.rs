@@ -3,6 +3,18 @@
 //
 
 use crate::camera::Rgb;
+use minifb::Key;
+
+// controls
+pub const LEFT_KEYS: [Key; 2] = [Key::A, Key::Left];
+pub const RIGHT_KEYS: [Key; 2] = [Key::D, Key::Right];
+pub const UP_KEYS: [Key; 2] = [Key::W, Key::Up];
+pub const DOWN_KEYS: [Key; 2] = [Key::S, Key::Down];
+pub const JUMP_KEYS: [Key; 1] = [Key::Space];
+pub const GRAPPLE_KEYS: [Key; 2] = [Key::E, Key::LeftShift];
+pub const PAUSE_KEYS: [Key; 1] = [Key::Escape];
+pub const MENU_SELECT_KEYS: [Key; 2] = [Key::Enter, Key::Space];
+pub const MENU_BACK_KEYS: [Key; 2] = [Key::Escape, Key::Backspace];
 
 // colors :)
 pub const PLAYER_COLOR: Rgb = Rgb::from_u32(0xf00000);
@@ -10,27 +22,143 @@ pub const STATIC_OBJECT_COLOR: Rgb = Rgb::from_u32(0xff);
 pub const MOVING_OBJECT_COLOR: Rgb = Rgb::from_u32(0x6cc06);
 pub const MOVING_PLATFORM_INDICATOR_COLOR: Rgb = Rgb::from_u32(0xeeeeee);
 pub const CHECKPOINT_COLOR: Rgb = Rgb::from_u32(0xff00);
+pub const SWITCH_COLOR: Rgb = Rgb::from_u32(0x808080);
+pub const SWITCH_ON_COLOR: Rgb = Rgb::from_u32(0xff8000);
+pub const DOOR_COLOR: Rgb = Rgb::from_u32(0x8b4513);
+pub const KEY_COLORS: [Rgb; 3] = [
+    Rgb::from_u32(0xff4040),
+    Rgb::from_u32(0x40c0ff),
+    Rgb::from_u32(0xc040ff),
+];
+pub const HAZARD_COLOR: Rgb = Rgb::from_u32(0xff6a00);
+pub const ROUND_OBJECT_COLOR: Rgb = Rgb::from_u32(0x4080ff);
+pub const BUMPER_COLOR: Rgb = Rgb::from_u32(0xff40a0);
+pub const TURRET_COLOR: Rgb = Rgb::from_u32(0x909090);
+pub const PROJECTILE_COLOR: Rgb = Rgb::from_u32(0xffe040);
+pub const GEM_COLOR: Rgb = Rgb::from_u32(0x40ffa0);
+pub const HUD_TEXT_COLOR: Rgb = Rgb::from_u32(0xffffff);
+pub const HUD_SHADOW_COLOR: Rgb = Rgb::from_u32(0x000000);
+pub const MENU_BACKGROUND_COLOR: Rgb = Rgb::from_u32(0x101020);
+pub const MENU_ITEM_COLOR: Rgb = Rgb::from_u32(0x303050);
+pub const MENU_SELECTED_COLOR: Rgb = Rgb::from_u32(0x7070e0);
+pub const MENU_TEXT_COLOR: Rgb = Rgb::from_u32(0xffffff);
+pub const MENU_TOGGLE_ON_COLOR: Rgb = Rgb::from_u32(0x40e040);
+pub const MENU_TOGGLE_OFF_COLOR: Rgb = Rgb::from_u32(0x181828);
+pub const LEVEL_LOCKED_COLOR: Rgb = Rgb::from_u32(0x202020);
+pub const LEVEL_COMPLETED_COLOR: Rgb = Rgb::from_u32(0x207040);
+pub const GEM_MISSING_COLOR: Rgb = Rgb::from_u32(0x104030);
+pub const TELEPORTER_COLOR: Rgb = Rgb::from_u32(0x00e0e0);
+pub const WIND_VOLUME_COLOR: Rgb = Rgb::from_u32(0xc0c0c0);
+pub const LOW_GRAVITY_VOLUME_COLOR: Rgb = Rgb::from_u32(0x4040ff);
+pub const INVERTED_GRAVITY_VOLUME_COLOR: Rgb = Rgb::from_u32(0xff40ff);
+pub const WATER_COLOR: Rgb = Rgb::from_u32(0x1060ff);
+pub const CLIMBABLE_VOLUME_COLOR: Rgb = Rgb::from_u32(0x20c020);
+pub const GRAPPLE_ANCHOR_COLOR: Rgb = Rgb::from_u32(0xd0d0d0);
+pub const ROPE_COLOR: Rgb = Rgb::from_u32(0xc8a060);
+pub const HEALTH_COLOR: Rgb = Rgb::from_u32(0xff3050);
+pub const HEALTH_LOST_COLOR: Rgb = Rgb::from_u32(0x502030);
+pub const CRATE_COLOR: Rgb = Rgb::from_u32(0xa0703c);
+pub const GHOST_COLOR: Rgb = Rgb::from_u32(0xe0e0ff);
+pub const TIMER_COLOR: Rgb = Rgb::from_u32(0xf0f0f0);
 pub const GOAL_COLOR: Rgb = Rgb::from_u32(0xf6f70b);
 pub const BACKGROUND_COLOR: Rgb = Rgb::from_u32(0x200020);
 pub const VOID_COLOR: Rgb = Rgb::from_u32(0x100010);
 
+// how strongly volumes tint the background
+pub const VOLUME_TINT_AMOUNT: f64 = 0.15;
+pub const WATER_TINT_AMOUNT: f64 = 0.4;
+pub const GHOST_BLEND_AMOUNT: f64 = 0.35;
+pub const PAUSE_DIM_AMOUNT: f64 = 0.6;
+
 // sizes
 pub const MOVING_PLATFORM_INDICATOR_RADIUS: f64 = 5.0;
 pub const VOID_TRANSITION_SIZE: f64 = 60.0;
+pub const PROJECTILE_RADIUS: f64 = 5.0;
+pub const GEM_RADIUS: f64 = 9.0;
+
+pub const HUD_MARGIN: usize = 12;
+pub const HUD_ICON_SIZE: usize = 16;
+
+// text is drawn with each of the font's pixels as a square this many pixels wide
+pub const HUD_TEXT_SCALE: usize = 3;
+pub const TIMER_TEXT_SCALE: usize = 4;
+pub const MENU_TEXT_SCALE: usize = 4;
+pub const MENU_HEADING_SCALE: usize = 8;
+pub const LEVEL_NUMBER_SCALE: usize = 5;
+
+pub const MENU_ITEM_WIDTH: usize = 640;
+pub const MENU_ITEM_HEIGHT: usize = 64;
+pub const MENU_ITEM_SPACING: usize = 16;
+pub const MENU_TOGGLE_SIZE: usize = 30;
+pub const MENU_SELECTED_BORDER: usize = 5;
+pub const LEVEL_SELECT_COLUMNS: usize = 7;
+pub const LEVEL_TILE_SIZE: usize = 100;
+pub const LEVEL_TILE_SPACING: usize = 20;
+
+pub const ROPE_THICKNESS: f64 = 3.0;
 
 pub const PLAYER_WIDTH: f64 = 20.0;
 pub const PLAYER_HEIGHT: f64 = 40.0;
+pub const PLAYER_CROUCH_HEIGHT: f64 = 24.0;
+
+// time trials (run with this argument)
+pub const TIME_TRIAL_ARGUMENT: &str = "--time-trial";
+
+// the save file, which is kept in a folder in the user's data directory
+// (bump the version whenever the format changes, and migrate the old one)
+pub const SAVE_VERSION: u32 = 2;
+pub const SAVE_DIRECTORY_NAME: &str = "platformer";
+pub const SAVE_FILE_NAME: &str = "save.txt";
+pub const SAVE_HEADER: &str = "platformer save";
+pub const GHOST_DIRECTORY: &str = "ghosts";
+
+// where records were kept before there was a save file (next to the game)
+pub const LEGACY_RECORDS_FILE: &str = "records.txt";
+
+// exporting and importing time trials as splits files (--export-splits path)
+pub const EXPORT_SPLITS_ARGUMENT: &str = "--export-splits";
+pub const IMPORT_SPLITS_ARGUMENT: &str = "--import-splits";
+pub const SPLITS_GAME_NAME: &str = "Platformer";
+pub const SPLITS_CATEGORY_NAME: &str = "All Levels";
+
+// levels
+pub const LEVEL_COUNT: u32 = 21;
 
 // window stuff
 pub const WINDOW_WIDTH: usize = 260 * 4;
 pub const WINDOW_HEIGHT: usize = 260 * 3;
 pub const FPS: f64 = 144.0;
 
+// simulation stuff (the game is simulated in fixed ticks of this many
+// hundredths of a second, and only catches up so many ticks each frame)
+pub const SIMULATION_TICK_HUNDREDTH_SECONDS: f64 = 100.0 / FPS;
+pub const MAX_TICKS_PER_FRAME: u32 = 8;
+
 // player stuff
 pub const PLAYER_WALKING_ACCEL: f64 = 2.4;
 pub const PLAYER_AIR_ACCELERATION_RATIO: f64 = 0.05;
+pub const PLAYER_CROUCH_SPEED_RATIO: f64 = 0.45;
 pub const COYOTE_TIME_HUNDREDTH_SECONDS: f64 = 8.0;
-pub const STUCK_PLATFORM_VELOCITY_ADD_MODIFIER: f64 = 0.6;
+
+// how many pixels the player can clip a corner or miss a ledge by and still be nudged around it
+pub const CORNER_CORRECTION_TOLERANCE: f64 = 8.0;
+pub const LEDGE_NUDGE_TOLERANCE: f64 = 6.0;
+
+// how far up a contact with a round object has to point to count as standing on it
+pub const ROUND_GROUND_NORMAL_Y: f64 = 0.5;
+
+// how close a moving object has to be to a stuck body to have pushed it there,
+// and how far a body can overlap something before it counts as stuck
+pub const CRUSH_CONTACT_DISTANCE: f64 = 1.5;
+pub const CRUSH_OVERLAP_TOLERANCE: f64 = 0.5;
+
+// how far below the player a slope can be and still have them snapped down onto it
+pub const SLOPE_SNAP_DISTANCE: f64 = 8.0;
+
+// ledge grab stuff
+pub const LEDGE_GRAB_REACH: f64 = 2.0;
+pub const LEDGE_GRAB_TOLERANCE: f64 = 10.0;
+pub const LEDGE_REGRAB_COOLDOWN_HUNDREDTH_SECONDS: f64 = 30.0;
 
 // jump stuff
 pub const JUMP_FORCE: f64 = 5.0;
@@ -40,6 +168,36 @@ pub const JUMP_BUFFER_HUNDREDTH_SECONDS: f64 = 1.0;
 pub const FRICTION_GROUND: f64 = 0.7;
 pub const FRICTION_AIR: f64 = 0.04;
 
+// teleporter stuff
+pub const TELEPORT_COOLDOWN_HUNDREDTH_SECONDS: f64 = 60.0;
+
+// climbing stuff
+pub const CLIMB_SPEED: f64 = 2.0;
+pub const CLIMB_SIDE_SPEED: f64 = 0.8;
+
+// grapple stuff
+pub const GRAPPLE_RANGE: f64 = 300.0;
+
+// water stuff
+pub const WATER_GRAVITY_SCALE: f64 = 0.3;
+pub const WATER_DRAG: f64 = 0.05;
+pub const SWIM_STROKE_FORCE: f64 = 3.0;
+pub const WATER_EXIT_BOOST: f64 = 2.0;
+
+// health stuff
+pub const HAZARD_DAMAGE: u32 = 1;
+pub const INVULNERABILITY_HUNDREDTH_SECONDS: f64 = 150.0;
+pub const INVULNERABILITY_FLASH_HUNDREDTH_SECONDS: f64 = 10.0;
+pub const KNOCKBACK_HORIZONTAL_SPEED: f64 = 3.0;
+pub const KNOCKBACK_VERTICAL_SPEED: f64 = 3.5;
+
+// turret stuff
+pub const PROJECTILE_DAMAGE: u32 = 1;
+pub const PROJECTILE_LIFETIME_HUNDREDTH_SECONDS: f64 = 800.0;
+
+// door stuff
+pub const DOOR_OPEN_TIME: f64 = 50.0;
+
 // camera stuff
 pub const PERCENT_SCREEN_PLAYER_ALLOWED_IN_X: f64 = 18.0;
 pub const PERCENT_SCREEN_PLAYER_ALLOWED_IN_Y: f64 = 17.5;
@@ -0,0 +1,144 @@
+use super::{
+    camera::Rgb,
+    constants::{WINDOW_HEIGHT, WINDOW_WIDTH},
+};
+
+// the size of each glyph (before it's scaled), and the gap between them
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+const GLYPH_SPACING: usize = 1;
+
+// the first character with a glyph
+const FIRST_GLYPH: char = ' ';
+
+/// the glyphs from the space to Z in ascii order, where each row is
+/// five pixels with the leftmost in the fifth bit (lowercase letters
+/// are drawn as uppercase, and anything else is drawn as a ?)
+const GLYPHS: [[u8; GLYPH_HEIGHT]; 59] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04], // !
+    [0x0a, 0x0a, 0x0a, 0x00, 0x00, 0x00, 0x00], // "
+    [0x0a, 0x0a, 0x1f, 0x0a, 0x1f, 0x0a, 0x0a], // #
+    [0x04, 0x0f, 0x14, 0x0e, 0x05, 0x1e, 0x04], // $
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03], // %
+    [0x0c, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0d], // &
+    [0x0c, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00], // '
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02], // (
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08], // )
+    [0x00, 0x04, 0x15, 0x0e, 0x15, 0x04, 0x00], // *
+    [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00], // +
+    [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08], // ,
+    [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00], // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c], // .
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00], // /
+    [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e], // 0
+    [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e], // 1
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f], // 2
+    [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e], // 3
+    [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02], // 4
+    [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e], // 5
+    [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e], // 6
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // 7
+    [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e], // 8
+    [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c], // 9
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00], // :
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x04, 0x08], // ;
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02], // <
+    [0x00, 0x00, 0x1f, 0x00, 0x1f, 0x00, 0x00], // =
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08], // >
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // ?
+    [0x0e, 0x11, 0x01, 0x0d, 0x15, 0x15, 0x0e], // @
+    [0x0e, 0x11, 0x11, 0x11, 0x1f, 0x11, 0x11], // A
+    [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e], // B
+    [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e], // C
+    [0x1c, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1c], // D
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f], // E
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10], // F
+    [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f], // G
+    [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // H
+    [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // I
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c], // J
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // K
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f], // L
+    [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11], // M
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // N
+    [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // O
+    [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10], // P
+    [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d], // Q
+    [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11], // R
+    [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e], // S
+    [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // T
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // U
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04], // V
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a], // W
+    [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11], // X
+    [0x11, 0x11, 0x11, 0x0a, 0x04, 0x04, 0x04], // Y
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f], // Z
+];
+
+// returns the glyph a character is drawn with
+fn glyph(character: char) -> &'static [u8; GLYPH_HEIGHT] {
+    let index = (character.to_ascii_uppercase() as usize).wrapping_sub(FIRST_GLYPH as usize);
+
+    GLYPHS
+        .get(index)
+        .unwrap_or(&GLYPHS['?' as usize - FIRST_GLYPH as usize])
+}
+
+/// returns how many pixels wide text is when drawn at a scale
+pub fn text_width(text: &str, scale: usize) -> usize {
+    let characters = text.chars().count();
+    (characters * (GLYPH_WIDTH + GLYPH_SPACING)).saturating_sub(GLYPH_SPACING) * scale
+}
+
+/// returns how many pixels tall text is when drawn at a scale
+pub fn text_height(scale: usize) -> usize {
+    GLYPH_HEIGHT * scale
+}
+
+/// draws text with its top left corner at a point, with each of the
+/// font's pixels as a square the scale wide (anything off screen is cut off)
+pub fn render_text(
+    text: &str,
+    left: usize,
+    top: usize,
+    scale: usize,
+    color: Rgb,
+    buffer: &mut [u32],
+) {
+    let color = color.to_u32();
+
+    for (index, character) in text.chars().enumerate() {
+        let glyph_left = left + index * (GLYPH_WIDTH + GLYPH_SPACING) * scale;
+
+        for (row, bits) in glyph(character).iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits >> (GLYPH_WIDTH - 1 - column) & 1 == 0 {
+                    continue;
+                }
+
+                let pixel_left = glyph_left + column * scale;
+                let pixel_top = top + row * scale;
+
+                for y in pixel_top..usize::min(pixel_top + scale, WINDOW_HEIGHT) {
+                    for x in pixel_left..usize::min(pixel_left + scale, WINDOW_WIDTH) {
+                        buffer[y * WINDOW_WIDTH + x] = color;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// draws text centered horizontally on a point, with its top at the given height
+pub fn render_centered_text(
+    text: &str,
+    center_x: usize,
+    top: usize,
+    scale: usize,
+    color: Rgb,
+    buffer: &mut [u32],
+) {
+    let left = center_x.saturating_sub(text_width(text, scale) / 2);
+    render_text(text, left, top, scale, color, buffer);
+}
@@ -1,32 +1,316 @@
 use super::{
     camera::{Camera, Rgb},
     constants::{
-        BACKGROUND_COLOR, CHECKPOINT_COLOR, FRICTION_AIR, FRICTION_GROUND, GRAVITY_MOVING_DOWN,
-        GRAVITY_MOVING_UP, JUMP_BUFFER_HUNDREDTH_SECONDS, JUMP_FORCE, MOVING_OBJECT_COLOR,
-        MOVING_PLATFORM_INDICATOR_COLOR, PLAYER_AIR_ACCELERATION_RATIO, PLAYER_COLOR,
-        PLAYER_WALKING_ACCEL, STATIC_OBJECT_COLOR, STUCK_PLATFORM_VELOCITY_ADD_MODIFIER,
-        VERTICAL_VELOCITY_ON_OR_UNDER_OBJECT, VOID_COLOR, VOID_TRANSITION_SIZE, WINDOW_HEIGHT,
-        WINDOW_WIDTH,
+        BACKGROUND_COLOR, CHECKPOINT_COLOR, CLIMBABLE_VOLUME_COLOR, CLIMB_SIDE_SPEED, CLIMB_SPEED,
+        CRATE_COLOR, DOOR_COLOR, DOWN_KEYS, FRICTION_AIR, FRICTION_GROUND, GEM_COLOR,
+        GHOST_BLEND_AMOUNT, GHOST_COLOR, GRAPPLE_ANCHOR_COLOR, GRAPPLE_KEYS, GRAVITY_MOVING_DOWN,
+        GRAVITY_MOVING_UP, INVERTED_GRAVITY_VOLUME_COLOR, INVULNERABILITY_FLASH_HUNDREDTH_SECONDS,
+        JUMP_BUFFER_HUNDREDTH_SECONDS, JUMP_FORCE, JUMP_KEYS, LEDGE_GRAB_REACH,
+        LEDGE_GRAB_TOLERANCE, LEDGE_REGRAB_COOLDOWN_HUNDREDTH_SECONDS, LEFT_KEYS,
+        LOW_GRAVITY_VOLUME_COLOR, MAX_TICKS_PER_FRAME, MOVING_OBJECT_COLOR,
+        MOVING_PLATFORM_INDICATOR_COLOR, PAUSE_KEYS, PLAYER_AIR_ACCELERATION_RATIO, PLAYER_COLOR,
+        PLAYER_CROUCH_HEIGHT, PLAYER_CROUCH_SPEED_RATIO, PLAYER_HEIGHT, PLAYER_WALKING_ACCEL,
+        PROJECTILE_DAMAGE, RIGHT_KEYS, ROPE_COLOR, ROPE_THICKNESS,
+        SIMULATION_TICK_HUNDREDTH_SECONDS, STATIC_OBJECT_COLOR, SWIM_STROKE_FORCE, SWITCH_COLOR,
+        SWITCH_ON_COLOR, TELEPORTER_COLOR, TELEPORT_COOLDOWN_HUNDREDTH_SECONDS, UP_KEYS,
+        VERTICAL_VELOCITY_ON_OR_UNDER_OBJECT, VOID_COLOR, VOID_TRANSITION_SIZE, VOLUME_TINT_AMOUNT,
+        WATER_COLOR, WATER_EXIT_BOOST, WATER_TINT_AMOUNT, WINDOW_HEIGHT, WINDOW_WIDTH,
+        WIND_VOLUME_COLOR,
     },
-    map::Map,
-    objects::{CollisionTypes, MovingObject, RectObject, Vector2},
+    hud::{render_hud, HudStats},
+    map::{key_color, Carrier, Crate, Map, VolumeEffects},
+    menu::{pause_menu, MenuItem},
+    objects::{CollisionTypes, LineSegment, RectObject, RigidBody, Vector2, VolumeEffect},
 };
 
 use crate::constants::COYOTE_TIME_HUNDREDTH_SECONDS;
+use crate::records::Ghost;
 use minifb::{Key, KeyRepeat, Window};
 
+// returns if any of the keys are held down
+fn any_key_down(window: &Window, keys: &[Key]) -> bool {
+    keys.iter().any(|key| window.is_key_down(*key))
+}
+
+// returns if any of the keys were just pressed
+pub fn any_key_pressed(window: &Window, keys: &[Key]) -> bool {
+    keys.iter()
+        .any(|key| window.is_key_pressed(*key, KeyRepeat::No))
+}
+
+// finds the acceleration gravity, volumes and friction put on a body
+fn physics_acceleration(
+    body: &RigidBody,
+    volume_effects: &VolumeEffects,
+    friction: f64,
+) -> Vector2 {
+    let mut acceleration = Vector2::new(0.0, 0.0);
+
+    // configure vertical acceleration (gravity), which is
+    // weaker while the body is moving away from the ground
+    let gravity = match body.velocity.y * volume_effects.gravity_direction <= 0.0 {
+        false => GRAVITY_MOVING_UP,
+        true => GRAVITY_MOVING_DOWN,
+    };
+    acceleration.y = gravity * volume_effects.gravity_scale * volume_effects.gravity_direction;
+
+    // configure acceleration from wind and drag
+    volume_effects.wind.add_to(&mut acceleration);
+    Vector2::multiply(&body.velocity, -volume_effects.drag).add_to(&mut acceleration);
+
+    // find and apply horizontal friction
+    let current_friction = f64::min(body.velocity.x.abs(), friction * body.velocity.x.abs());
+
+    acceleration.x += match body.velocity.x < 0.0 {
+        true => current_friction,
+        false => -current_friction,
+    };
+
+    acceleration
+}
+
+// returns how far a carrier moved this frame, or none if
+// the body has moved off of it (or it no longer exists)
+fn carrier_move(
+    map: &Map,
+    crates: &[Crate],
+    carrier: Carrier,
+    body: &RigidBody,
+) -> Option<Vector2> {
+    match carrier {
+        Carrier::MovingObject(index) => map
+            .moving_objects
+            .get(index)
+            .filter(|object| body.collides_with_y(*object))
+            .map(|object| object.prev_move()),
+        Carrier::Crate(index) => crates
+            .get(index)
+            .filter(|current| body.collides_with_y(*current))
+            .map(|current| current.prev_move),
+    }
+}
+
+// returns how fast a carrier was moving this frame
+fn carrier_velocity(map: &Map, carrier: Carrier, frame_time: f64) -> Vector2 {
+    match carrier {
+        Carrier::MovingObject(index) => map.moving_objects[index].velocity(),
+        Carrier::Crate(index) => match (map.crates.get(index), frame_time > 0.0) {
+            (Some(current), true) => Vector2::multiply(&current.prev_move, 1.0 / frame_time),
+            _ => Vector2::new(0.0, 0.0),
+        },
+    }
+}
+
+// handles a crate's collisions with the level and every other crate,
+// returning the index of the switch it's resting on, if any
+fn collide_crate(map: &Map, crates: &mut [Crate], index: usize) -> Option<usize> {
+    let other_crates: Vec<RigidBody> = crates
+        .iter()
+        .enumerate()
+        .filter(|(other, _)| *other != index)
+        .map(|(_, other)| other.body)
+        .collect();
+
+    let current = &mut crates[index];
+    let gravity_direction = map
+        .volume_effects_at(&current.body.center)
+        .gravity_direction;
+    let contacts =
+        map.collide_with_level(&mut current.body, &mut current.collision, gravity_direction);
+    let crate_on = current
+        .body
+        .handle_collisions(&other_crates, &mut current.collision, gravity_direction)
+        // other_crates skips this crate, so indices after it are one lower
+        .map(|other| match other < index {
+            true => other,
+            false => other + 1,
+        });
+
+    current.carrier = contacts
+        .platform_on
+        .map(Carrier::MovingObject)
+        .or(crate_on.map(Carrier::Crate));
+
+    contacts.switch_on
+}
+
+// moves a crate and handles its collisions, returning
+// the index of the switch it's resting on, if any
+fn update_crate(map: &Map, crates: &mut [Crate], index: usize, frame_time: f64) -> Option<usize> {
+    let start = crates[index].body.center;
+
+    // ride along with whatever the crate was resting on
+    if let Some(carrier) = crates[index].carrier {
+        if let Some(movement) = carrier_move(map, crates, carrier, &crates[index].body) {
+            movement.add_to(&mut crates[index].body.center);
+        }
+    }
+
+    let volume_effects = map.volume_effects_at(&crates[index].body.center);
+
+    // crates slide less when they're on the ground
+    let friction = match crates[index].collision.contains(&volume_effects.ground()) {
+        true => FRICTION_GROUND,
+        false => FRICTION_AIR,
+    };
+
+    let acceleration = physics_acceleration(&crates[index].body, &volume_effects, friction);
+    crates[index].body.integrate(&acceleration, frame_time);
+
+    crates[index].collision = Vec::new();
+    let switch_on = collide_crate(map, crates, index);
+
+    // stop the crate when it runs into things
+    let current = &mut crates[index];
+    if current.collision.contains(&CollisionTypes::Left)
+        || current.collision.contains(&CollisionTypes::Right)
+    {
+        current.body.velocity.x = 0.0;
+    }
+
+    if current.collision.contains(&CollisionTypes::Top)
+        || current.collision.contains(&CollisionTypes::Bottom)
+    {
+        current.body.velocity.y =
+            VERTICAL_VELOCITY_ON_OR_UNDER_OBJECT * volume_effects.gravity_direction;
+    }
+
+    current.prev_move = Vector2::subtract(&current.body.center, &start);
+
+    switch_on
+}
+
+/// the top corner of an object the player is hanging from
+#[derive(Clone, Copy)]
+struct LedgeGrab {
+    /// the index of the object in the map's static or moving objects
+    object: usize,
+    moving: bool,
+    /// which side of the object the player is on
+    side: CollisionTypes,
+}
+
+impl LedgeGrab {
+    // returns where the corner the player is holding is
+    fn corner(&self, map: &Map) -> Vector2 {
+        let points = match self.moving {
+            true => map.moving_objects[self.object].points(),
+            false => map.static_objects[self.object].points(),
+        };
+
+        // points start at the top left and go clockwise
+        match self.side {
+            CollisionTypes::Left => points[0],
+            _ => points[1],
+        }
+    }
+
+    // returns the player hanging with their hands on the corner
+    fn hanging_player(&self, map: &Map) -> RigidBody {
+        let corner = self.corner(map);
+        let side_offset = match self.side {
+            CollisionTypes::Left => -map.player.width / 2.0,
+            _ => map.player.width / 2.0,
+        };
+
+        RigidBody {
+            center: Vector2::new(corner.x + side_offset, corner.y - map.player.height / 2.0),
+            velocity: Vector2::new(0.0, 0.0),
+            ..map.player
+        }
+    }
+
+    // returns the player standing on top of the ledge
+    fn mantled_player(&self, map: &Map) -> RigidBody {
+        let corner = self.corner(map);
+        let side_offset = match self.side {
+            CollisionTypes::Left => map.player.width / 2.0,
+            _ => -map.player.width / 2.0,
+        };
+
+        RigidBody {
+            center: Vector2::new(corner.x + side_offset, corner.y + map.player.height / 2.0),
+            velocity: Vector2::new(0.0, 0.0),
+            ..map.player
+        }
+    }
+}
+
+// finds a top corner of a static or moving object the player
+// is beside (on the given side) with their hands level with it
+fn find_ledge(map: &Map, side: CollisionTypes) -> Option<LedgeGrab> {
+    let player_bounds = map.player.bounds();
+
+    let grabbable = |grab: &LedgeGrab| {
+        let corner = grab.corner(map);
+        let player_edge = match side {
+            CollisionTypes::Left => player_bounds.right,
+            _ => player_bounds.left,
+        };
+
+        (corner.x - player_edge).abs() <= LEDGE_GRAB_REACH
+            && corner.y >= player_bounds.top
+            && corner.y - player_bounds.top <= LEDGE_GRAB_TOLERANCE
+            // don't grab corners the player couldn't climb onto
+            && !map.overlaps_solid(&grab.mantled_player(map))
+    };
+
+    let static_ledges = (0..map.static_objects.len()).map(|object| LedgeGrab {
+        object,
+        moving: false,
+        side,
+    });
+    let moving_ledges = (0..map.moving_objects.len()).map(|object| LedgeGrab {
+        object,
+        moving: true,
+        side,
+    });
+
+    static_ledges.chain(moving_ledges).find(grabbable)
+}
+
+/// everything the player is carrying
+#[derive(Clone)]
+struct Inventory {
+    keys: Vec<u32>,
+}
+
+impl Inventory {
+    fn new() -> Inventory {
+        Inventory { keys: Vec::new() }
+    }
+}
+
 // this is the function we use to render the game
 fn render_game(world_point: Vector2, map: &Map) -> Rgb {
-    let rgb: Rgb;
+    let mut rgb: Rgb;
+
+    // determine collision with player (who flickers while they can't be hurt)
+    let player_flicker = map.invulnerability > 0.0
+        && (map.invulnerability / INVULNERABILITY_FLASH_HUNDREDTH_SECONDS) as u32 % 2 == 1;
+    let player_collision = !player_flicker && map.player.contains_point(&world_point);
 
-    // determine collision with player
-    let player_collision = map.player.contains_point(&world_point);
+    // determine collision with the player's rope
+    let rope_collision = map.rope.is_some_and(|rope| {
+        LineSegment::new(rope.anchor, map.player.center).distance_to_point(&world_point)
+            < ROPE_THICKNESS / 2.0
+    });
+
+    // determine collision with crates
+    let crate_collision = map
+        .crates
+        .iter()
+        .any(|current| current.contains_point(&world_point));
 
     // determine collision with static objects
     let static_object_collision = map
         .static_objects
         .iter()
-        .any(|object| object.bounds().contains_point(&world_point));
+        .any(|object| object.bounds().contains_point(&world_point))
+        || map
+            .slopes
+            .iter()
+            .any(|slope| slope.contains_point(&world_point));
 
     // determine collision with moving objects
     let moving_object_collision = map
@@ -34,6 +318,18 @@ fn render_game(world_point: Vector2, map: &Map) -> Rgb {
         .iter()
         .any(|object| object.bounds().contains_point(&world_point));
 
+    // determine collision with switches
+    let switch_collision = map
+        .switches
+        .iter()
+        .find(|switch| switch.bounds().contains_point(&world_point));
+
+    // determine collision with doors
+    let door_collision = map
+        .doors
+        .iter()
+        .find(|door| !door.is_open() && door.bounds().contains_point(&world_point));
+
     // determine if there should be any rendering of circles
     let mut circle_color: Option<Rgb> = None;
     if map
@@ -52,247 +348,923 @@ fn render_game(world_point: Vector2, map: &Map) -> Rgb {
         circle_color = Some(CHECKPOINT_COLOR);
     }
 
+    if let Some(door_key) = map
+        .door_keys
+        .iter()
+        .find(|door_key| !door_key.collected && door_key.indicator.contains_point(&world_point))
+    {
+        circle_color = Some(door_key.indicator.color);
+    }
+
+    if map
+        .gems
+        .iter()
+        .any(|gem| !gem.collected && gem.indicator.contains_point(&world_point))
+    {
+        circle_color = Some(GEM_COLOR);
+    }
+
+    if let Some(hazard) = map
+        .hazards
+        .iter()
+        .find(|hazard| hazard.circle.contains_point(&world_point))
+    {
+        circle_color = Some(hazard.circle.color);
+    }
+
+    if map.teleporters.iter().any(|teleporter| {
+        teleporter
+            .ends
+            .iter()
+            .any(|end| end.contains_point(&world_point))
+    }) {
+        circle_color = Some(TELEPORTER_COLOR);
+    }
+
+    if map
+        .grapple_anchors
+        .iter()
+        .any(|anchor| anchor.contains_point(&world_point))
+    {
+        circle_color = Some(GRAPPLE_ANCHOR_COLOR);
+    }
+
+    if let Some(object) = map
+        .round_objects
+        .iter()
+        .find(|object| object.circle.contains_point(&world_point))
+    {
+        circle_color = Some(object.circle.color);
+    }
+
+    if let Some(turret) = map
+        .turrets
+        .iter()
+        .find(|turret| turret.circle.contains_point(&world_point))
+    {
+        circle_color = Some(turret.circle.color);
+    }
+
+    if let Some(projectile) = map
+        .projectiles
+        .iter()
+        .find(|projectile| projectile.circle.contains_point(&world_point))
+    {
+        circle_color = Some(projectile.circle.color);
+    }
+
     if map.goal.contains_point(&world_point) {
         circle_color = Some(map.goal.color);
     }
 
+    // tint the background with the color of any volume here
+    let mut background_color = BACKGROUND_COLOR;
+    for volume in &map.volumes {
+        if volume.contains_point(&world_point) {
+            let volume_color = match volume.effect {
+                VolumeEffect::Wind(_) => WIND_VOLUME_COLOR,
+                VolumeEffect::GravityScale(_) => LOW_GRAVITY_VOLUME_COLOR,
+                VolumeEffect::InvertGravity => INVERTED_GRAVITY_VOLUME_COLOR,
+                VolumeEffect::Climbable => CLIMBABLE_VOLUME_COLOR,
+                // water is tinted over everything below
+                VolumeEffect::Water => continue,
+            };
+
+            background_color = background_color.blend(VOLUME_TINT_AMOUNT, volume_color);
+        }
+    }
+
     // find the proper rgb value
     if player_collision {
         rgb = PLAYER_COLOR;
+    } else if rope_collision {
+        rgb = ROPE_COLOR;
     } else if moving_object_collision {
         rgb = MOVING_OBJECT_COLOR;
+    } else if crate_collision {
+        rgb = CRATE_COLOR;
     } else if static_object_collision {
         rgb = STATIC_OBJECT_COLOR;
+    } else if let Some(door) = door_collision {
+        rgb = match door.key() {
+            Some(key) => key_color(key).blend(0.5, DOOR_COLOR),
+            None => DOOR_COLOR,
+        };
+    } else if let Some(switch) = switch_collision {
+        rgb = match switch.on {
+            true => SWITCH_ON_COLOR,
+            false => SWITCH_COLOR,
+        };
     } else if let Some(color) = circle_color {
         rgb = color;
     } else if world_point.y > map.lowest_point + VOID_TRANSITION_SIZE / 2.0 {
-        rgb = BACKGROUND_COLOR;
+        rgb = background_color;
     } else if world_point.y < map.lowest_point - VOID_TRANSITION_SIZE / 2.0 {
         rgb = VOID_COLOR;
     } else {
         let distance_in = map.lowest_point + VOID_TRANSITION_SIZE / 2.0 - world_point.y;
         let blend_amount = distance_in / VOID_TRANSITION_SIZE;
-        rgb = BACKGROUND_COLOR.blend(blend_amount, VOID_COLOR);
+        rgb = background_color.blend(blend_amount, VOID_COLOR);
     }
 
-    rgb
-}
-
-/**
-  plays a game with a supplied map and window
-* function will end when the player beats the level or presses escape
-* returns if the user tried to terminate the entire program
-*/
-pub fn play_game(map: &mut Map, window: &mut Window) -> bool {
-    // this will be where we write out pixel values
-    let mut window_buffer: Vec<u32> = vec![0; WINDOW_WIDTH * WINDOW_HEIGHT];
+    // blend the ghost over everything but the player
+    if !player_collision
+        && map
+            .ghost
+            .is_some_and(|ghost| ghost.contains_point(&world_point))
+    {
+        rgb = rgb.blend(GHOST_BLEND_AMOUNT, GHOST_COLOR);
+    }
 
-    // create our camera
-    let mut camera = Camera::new(map.player.center.x - WINDOW_WIDTH as f64 / 2.0, 0.0);
+    // blend water over whatever is behind it
+    for volume in &map.volumes {
+        if let VolumeEffect::Water = volume.effect {
+            if volume.contains_point(&world_point) {
+                rgb = rgb.blend(WATER_TINT_AMOUNT, WATER_COLOR);
+            }
+        }
+    }
 
-    // how long each frame takes (in hundreds of a seconds)
-    let mut frame_time: f64 = 0.0;
+    rgb
+}
 
+/// everything about the player that carries over between simulation ticks
+struct PlayerState {
     // jump buffers make movement feel a little better by jumping even if the
     // player clicks the jump button just before they land on the ground
-    let mut jump_buffer: f64 = 0.0;
+    jump_buffer: f64,
 
     // coyote time makes movement feel a little better by jumping
     // even if the player jumps just after leaving the ground
-    let mut coyote_time: f64 = 0.0;
+    coyote_time: f64,
 
     // this is where we'll store the player's active collision
-    let mut collision: Vec<CollisionTypes> = Vec::new();
+    collision: Vec<CollisionTypes>,
 
-    // when a player is on a moving platform, we "stick" them to
-    // the platform to stop them from bouncing on it as it moves
-    let mut stuck_platform: Option<MovingObject> = None;
+    // what the player is standing on, which carries
+    // them along by exactly as far as it moves
+    carrier: Option<Carrier>,
 
-    while window.is_open()
-        && (!window.is_key_pressed(Key::Key1, KeyRepeat::No)
-            && !window.is_key_pressed(Key::Escape, KeyRepeat::No))
-    {
-        // used to measure the frame time
-        let frame_start = std::time::Instant::now();
+    // if the player was in water last tick (for giving
+    // them a boost when they swim out the top)
+    was_in_water: bool,
 
-        //
-        // player movement and velocity
-        //
+    // if the player is holding onto a ladder or vine
+    climbing: bool,
 
-        // this is where the player's acceleration is stored
-        let mut player_acceleration_vector: Vector2 = Vector2::new(0.0, 0.0);
+    // the ledge the player is hanging from, and how long until
+    // they can grab one again after letting go
+    ledge_grab: Option<LedgeGrab>,
+    ledge_regrab_cooldown: f64,
 
-        // configure vertical acceleration (gravity)
-        player_acceleration_vector.y = match map.player.velocity.y <= 0.0 {
-            false => GRAVITY_MOVING_UP,
-            true => GRAVITY_MOVING_DOWN,
-        };
+    // if the player is crouching (and has a shorter hitbox)
+    crouching: bool,
+
+    // stops the player from being teleported again
+    // as soon as they come out of a teleporter
+    teleport_cooldown: f64,
+
+    // the teleporter and end the player last came out of, which
+    // can't send them back until they've stepped off of it
+    teleport_exit: Option<(usize, usize)>,
+
+    // the keys the player is carrying, and the keys they
+    // were carrying when they last reached a checkpoint
+    inventory: Inventory,
+    checkpoint_inventory: Inventory,
+}
+
+impl PlayerState {
+    fn new() -> PlayerState {
+        PlayerState {
+            jump_buffer: 0.0,
+            coyote_time: 0.0,
+            collision: Vec::new(),
+            carrier: None,
+            was_in_water: false,
+            climbing: false,
+            ledge_grab: None,
+            ledge_regrab_cooldown: 0.0,
+            crouching: false,
+            teleport_cooldown: 0.0,
+            teleport_exit: None,
+            inventory: Inventory::new(),
+            checkpoint_inventory: Inventory::new(),
+        }
+    }
+}
+
+/// the controls the player is using, read once per rendered frame
+#[derive(Clone, Copy, Default)]
+struct Input {
+    horizontal: f64,
+    vertical: f64,
+    grapple_held: bool,
+    grapple_pressed: bool,
+    jump_pressed: bool,
+    up_pressed: bool,
+}
 
-        // configure horizontal acceleration (movement)
-        let mut current_x_acceleration = PLAYER_WALKING_ACCEL;
-        if !collision.contains(&CollisionTypes::Top) {
-            current_x_acceleration *= PLAYER_AIR_ACCELERATION_RATIO;
+impl Input {
+    fn read(window: &Window) -> Input {
+        // find which directions the player is trying to move
+        let mut horizontal = 0.0;
+        if any_key_down(window, &RIGHT_KEYS) {
+            horizontal += 1.0;
+        }
+        if any_key_down(window, &LEFT_KEYS) {
+            horizontal -= 1.0;
         }
 
-        if window.is_key_down(Key::D) || window.is_key_down(Key::Right) {
-            player_acceleration_vector.x += current_x_acceleration;
+        let mut vertical = 0.0;
+        if any_key_down(window, &UP_KEYS) {
+            vertical += 1.0;
         }
-        if window.is_key_down(Key::A) || window.is_key_down(Key::Left) {
-            player_acceleration_vector.x -= current_x_acceleration;
+        if any_key_down(window, &DOWN_KEYS) {
+            vertical -= 1.0;
         }
 
-        // find horizontal acceleration
-        let current_friction = f64::min(
-            map.player.velocity.x.abs(),
-            match collision.contains(&CollisionTypes::Top) {
-                true => FRICTION_GROUND * map.player.velocity.x.abs(),
-                false => FRICTION_AIR * map.player.velocity.x.abs(),
-            },
-        );
+        Input {
+            horizontal,
+            vertical,
+            grapple_held: any_key_down(window, &GRAPPLE_KEYS),
+            grapple_pressed: any_key_pressed(window, &GRAPPLE_KEYS),
+            jump_pressed: any_key_pressed(window, &JUMP_KEYS),
+            up_pressed: any_key_pressed(window, &UP_KEYS),
+        }
+    }
 
-        // apply friction
-        player_acceleration_vector.x += match map.player.velocity.x < 0.0 {
-            true => current_friction,
-            false => -current_friction,
-        };
+    /// adds in any key presses from a frame that didn't get to simulate them
+    fn with_presses_from(self, unused: &Input) -> Input {
+        Input {
+            grapple_pressed: self.grapple_pressed || unused.grapple_pressed,
+            jump_pressed: self.jump_pressed || unused.jump_pressed,
+            up_pressed: self.up_pressed || unused.up_pressed,
+            ..self
+        }
+    }
+
+    /// returns the input with key presses removed, so a press
+    /// only counts for the first tick of a frame
+    fn held_only(self) -> Input {
+        Input {
+            grapple_pressed: false,
+            jump_pressed: false,
+            up_pressed: false,
+            ..self
+        }
+    }
+}
+
+/// things that happened during a simulation tick
+#[derive(Default)]
+struct TickEvents {
+    /// the player went through a teleporter
+    teleported: bool,
+    /// the player touched the goal
+    reached_goal: bool,
+    /// the player died and was sent back to their respawn point
+    died: bool,
+}
+
+// sends the player back to their last checkpoint with what they had there
+fn respawn(map: &mut Map, state: &mut PlayerState) {
+    map.respawn_player();
+    state.inventory = state.checkpoint_inventory.clone();
+    state.carrier = None;
+    state.ledge_grab = None;
+    state.crouching = false;
+    state.teleport_exit = None;
+}
+
+// advances the game by one simulation tick
+fn simulate_tick(
+    map: &mut Map,
+    state: &mut PlayerState,
+    input: &Input,
+    tick_time: f64,
+) -> TickEvents {
+    let mut events = TickEvents::default();
+
+    //
+    // player movement and velocity
+    //
 
-        // move the player (we integrate the player's movement to make
-        // the physics continuous and therefore framerate-independent)
-        let movement_vector = Vector2::add(
-            // accel * t^2 / 2
-            &Vector2::multiply(&player_acceleration_vector, frame_time * frame_time / 2.0),
-            // vel * t
-            &Vector2::multiply(&map.player.velocity, frame_time),
-            // c is already stored in the player's position
-            // and will be included when we add this movement
-            // vector to the player's current position
+    // find how the volumes the player is in change their physics, and
+    // which side of an object the player has to be on to be on the ground
+    let volume_effects = map.volume_effects_at(&map.player.center);
+    let ground = volume_effects.ground();
+
+    // find the friction on the player (there's no air friction
+    // while swinging so the player keeps their momentum)
+    let friction = match (state.collision.contains(&ground), map.rope.is_some()) {
+        (true, _) => FRICTION_GROUND,
+        (false, true) => 0.0,
+        (false, false) => FRICTION_AIR,
+    };
+
+    // this is where the player's acceleration is stored
+    let mut player_acceleration_vector =
+        physics_acceleration(&map.player, &volume_effects, friction);
+
+    // give the player a boost if they just swam out of water
+    if state.was_in_water
+        && !volume_effects.in_water
+        && map.player.velocity.y * volume_effects.gravity_direction > 0.0
+    {
+        map.player.velocity.y += WATER_EXIT_BOOST * volume_effects.gravity_direction;
+    }
+    state.was_in_water = volume_effects.in_water;
+
+    // configure horizontal acceleration (movement)
+    let mut current_x_acceleration = PLAYER_WALKING_ACCEL;
+    if !state.collision.contains(&ground) {
+        current_x_acceleration *= PLAYER_AIR_ACCELERATION_RATIO;
+    }
+
+    // find which directions the player is trying to move
+    let horizontal_input = input.horizontal;
+    let vertical_input = input.vertical;
+
+    // attach to the closest grapple anchor when the grapple key is
+    // pressed, and let go (keeping momentum) when it's released
+    if !input.grapple_held {
+        map.rope = None;
+    } else if input.grapple_pressed {
+        map.rope = map.find_grapple();
+    }
+
+    // while hanging from a ledge, climb up onto it with up
+    // or jump, or let go of it with down
+    let was_hanging = state.ledge_grab.is_some();
+    state.ledge_regrab_cooldown -= tick_time;
+
+    if let Some(grab) = state.ledge_grab {
+        if map.rope.is_some() {
+            state.ledge_grab = None;
+        } else if vertical_input > 0.0 || input.jump_pressed {
+            map.player = grab.mantled_player(map);
+            state.ledge_grab = None;
+        } else if vertical_input < 0.0 {
+            state.ledge_grab = None;
+            state.ledge_regrab_cooldown = LEDGE_REGRAB_COOLDOWN_HUNDREDTH_SECONDS;
+        }
+    }
+
+    // grab onto ladders and vines when pressing up or down on them,
+    // and let go when the player leaves them or grapples something
+    if !volume_effects.climbable || map.rope.is_some() || state.ledge_grab.is_some() {
+        state.climbing = false;
+    } else if vertical_input != 0.0 {
+        state.climbing = true;
+    }
+
+    // crouch while holding down, keeping the player's feet planted, and
+    // only stand back up if there's nothing in the way of their head
+    let wants_to_crouch =
+        vertical_input < 0.0 && !state.climbing && !was_hanging && map.rope.is_none();
+    let direction = volume_effects.gravity_direction;
+
+    if wants_to_crouch && !state.crouching {
+        map.player = map.player.with_height(PLAYER_CROUCH_HEIGHT, direction);
+        state.crouching = true;
+    } else if !wants_to_crouch && state.crouching {
+        let standing = map.player.with_height(PLAYER_HEIGHT, direction);
+        let headroom = standing.with_height(PLAYER_HEIGHT - PLAYER_CROUCH_HEIGHT, -direction);
+
+        if !map.overlaps_solid(&headroom) {
+            map.player = standing;
+            state.crouching = false;
+        }
+    }
+
+    if state.crouching {
+        current_x_acceleration *= PLAYER_CROUCH_SPEED_RATIO;
+    }
+
+    player_acceleration_vector.x += current_x_acceleration * horizontal_input;
+
+    // while climbing, the player moves at a fixed speed with no gravity
+    if state.climbing {
+        player_acceleration_vector = Vector2::new(0.0, 0.0);
+        map.player.velocity = Vector2::new(
+            horizontal_input * CLIMB_SIDE_SPEED,
+            vertical_input * CLIMB_SPEED,
         );
+    }
 
-        // apply the movement vector we calculated (adds c)
-        map.player.move_by(&movement_vector);
+    // hanging players don't move on their own
+    if state.ledge_grab.is_some() {
+        player_acceleration_vector = Vector2::new(0.0, 0.0);
+        map.player.velocity = Vector2::new(0.0, 0.0);
+    }
 
-        // update velocity (no integrating is needed as accel * t is exactly the growth in velocity)
-        Vector2::multiply(&player_acceleration_vector, frame_time).add_to(&mut map.player.velocity);
+    // move the player
+    map.player.integrate(&player_acceleration_vector, tick_time);
 
-        //
-        // moving platform stuff
-        //
+    // keep the player on the end of their rope
+    if let Some(rope) = map.rope {
+        rope.constrain(&mut map.player);
+        state.carrier = None;
+    }
+
+    //
+    // moving platform stuff
+    //
+
+    // advance the level clock and update the position of moving platforms
+    map.level_clock += tick_time;
+    for moving_object in &mut map.moving_objects {
+        moving_object.update(map.level_clock);
+    }
 
-        // update the position of moving platforms
-        for moving_object in &mut map.moving_objects {
-            moving_object.update(frame_time);
+    for hazard in &mut map.hazards {
+        hazard.update(map.level_clock);
+    }
+
+    map.update_turrets(tick_time);
+
+    //
+    // collision handling
+    //
+
+    // open any door the player is touching with its key
+    for door in &mut map.doors {
+        if door.is_opening() || !map.player.collides_with(door) {
+            continue;
         }
 
-        // move with the platform we're stuck to
-        if let Some(mut stuck_obj) = stuck_platform {
-            // only keep the player stuck if they're still on the platform
-            if map.player.collides_with_y(&stuck_obj) {
-                stuck_obj.update(frame_time);
-                map.player.center.x += stuck_obj.prev_move().x;
-                // move the player slightly into the platform to keep them stuck
-                map.player.center.y = stuck_obj.bounds().top + map.player.height / 2.0 - 0.01;
+        if let Some(key) = door.key() {
+            if let Some(slot) = state.inventory.keys.iter().position(|held| *held == key) {
+                state.inventory.keys.remove(slot);
+                door.open();
             }
         }
+    }
 
-        stuck_platform = None;
+    for door in &mut map.doors {
+        door.update(tick_time);
+    }
 
-        //
-        // collision handling
-        //
+    // move the crates (they're taken out of the map while
+    // we do this so they can collide with the rest of it)
+    let mut crates = std::mem::take(&mut map.crates);
+    let mut switches_under_crates: Vec<usize> = Vec::new();
 
-        // reset collision
-        collision = Vec::new();
+    // lower crates go first so crates stacked on them are carried the right amount
+    let mut order: Vec<usize> = (0..crates.len()).collect();
+    order.sort_by(|a, b| {
+        crates[*a]
+            .body
+            .center
+            .y
+            .total_cmp(&crates[*b].body.center.y)
+    });
 
-        // handle collisions with moving objects, and if we're stuck to
-        // an object, update the stuck_platform variable
-        if let Some(index) = map
-            .player
-            .handle_collisions(&map.moving_objects, &mut collision)
-        {
-            stuck_platform = Some(map.moving_objects[index].clone());
+    for index in order {
+        if let Some(switch) = update_crate(map, &mut crates, index, tick_time) {
+            switches_under_crates.push(switch);
         }
+    }
 
-        // handle collisions with static objects
-        map.player
-            .handle_collisions(&map.static_objects, &mut collision);
+    // let the player push crates, which stay where they are if they're blocked
+    for index in 0..crates.len() {
+        if map.player.push_sideways(&mut crates[index].body) {
+            crates[index].body.velocity.x = map.player.velocity.x;
+            collide_crate(map, &mut crates, index);
+        }
+    }
 
-        //
-        // final physics before rendering graphics
-        //
+    // crates that fall out of the level are gone, so anything riding on a crate
+    // is pointed at where it ended up, or drops off if it was one that's gone
+    let mut remaining = 0;
+    let new_indices: Vec<Option<usize>> = crates
+        .iter()
+        .map(|current| {
+            (current.body.center.y > map.lowest_point).then(|| {
+                remaining += 1;
+                remaining - 1
+            })
+        })
+        .collect();
+
+    let remap = |carrier: Option<Carrier>| match carrier {
+        Some(Carrier::Crate(index)) => new_indices[index].map(Carrier::Crate),
+        other => other,
+    };
+
+    crates.retain(|current| current.body.center.y > map.lowest_point);
+    for current in &mut crates {
+        current.carrier = remap(current.carrier);
+    }
+    state.carrier = remap(state.carrier);
+    map.crates = crates;
+
+    // move with whatever the player is standing on
+    if let Some(current) = state.carrier {
+        if let Some(movement) = carrier_move(map, &map.crates, current, &map.player) {
+            movement.add_to(&mut map.player.center);
+        }
+    }
+
+    // move with the ledge we're hanging from
+    if let Some(grab) = state.ledge_grab {
+        map.player = grab.hanging_player(map);
+    }
+
+    // reset collision
+    let was_grounded = state.collision.contains(&ground);
+    state.collision = Vec::new();
+
+    // handle collisions with the level and the crates
+    let mut player = map.player;
+    let gravity_direction = volume_effects.gravity_direction;
+    let contacts = map.collide_with_level(&mut player, &mut state.collision, gravity_direction);
+    let crate_on = player.handle_collisions(&map.crates, &mut state.collision, gravity_direction);
+
+    // keep the player on the ground when they run down a slope
+    if was_grounded
+        && !state.collision.contains(&ground)
+        && volume_effects.gravity_direction > 0.0
+        && player.velocity.y <= 0.0
+        && map.snap_to_slope(&mut player)
+    {
+        state.collision.push(CollisionTypes::Top);
+    }
+
+    map.player = player;
+
+    // let moving objects know if the player is standing on them
+    for (index, moving_object) in map.moving_objects.iter_mut().enumerate() {
+        moving_object.set_stood_on(contacts.platform_on == Some(index));
+    }
+
+    // remember what the player is standing on so they move with it
+    state.carrier = contacts
+        .platform_on
+        .map(Carrier::MovingObject)
+        .or(crate_on.map(Carrier::Crate));
+
+    // press the switches the player or a crate is standing on
+    for (index, switch) in map.switches.iter_mut().enumerate() {
+        switch.set_pressed(
+            contacts.switch_on == Some(index) || switches_under_crates.contains(&index),
+        );
+    }
+
+    map.apply_switches();
 
-        // decrease our jump buffer
-        jump_buffer -= frame_time;
+    //
+    // final physics
+    //
 
-        // handle coyote time
-        if collision.contains(&CollisionTypes::Top) {
-            coyote_time = COYOTE_TIME_HUNDREDTH_SECONDS;
-        } else if coyote_time > 0.0 {
-            coyote_time -= frame_time;
+    // decrease our jump buffer
+    state.jump_buffer -= tick_time;
+
+    // handle coyote time
+    if state.collision.contains(&ground) {
+        state.coyote_time = COYOTE_TIME_HUNDREDTH_SECONDS;
+    } else if state.coyote_time > 0.0 {
+        state.coyote_time -= tick_time;
+    }
+
+    // reset the player's velocity if they're
+    // on the side of an object
+    if state.collision.contains(&CollisionTypes::Left)
+        || state.collision.contains(&CollisionTypes::Right)
+    {
+        map.player.velocity.x = 0.0;
+    }
+
+    // grab a ledge when falling past one while pressing toward it
+    if state.ledge_grab.is_none()
+        && state.ledge_regrab_cooldown <= 0.0
+        && volume_effects.gravity_direction > 0.0
+        && map.player.velocity.y < 0.0
+        && !state.climbing
+        && map.rope.is_none()
+    {
+        if state.collision.contains(&CollisionTypes::Left) && horizontal_input > 0.0 {
+            state.ledge_grab = find_ledge(map, CollisionTypes::Left);
+        } else if state.collision.contains(&CollisionTypes::Right) && horizontal_input < 0.0 {
+            state.ledge_grab = find_ledge(map, CollisionTypes::Right);
         }
 
-        // reset the player's velocity if they're
-        // on the side of an object
-        if collision.contains(&CollisionTypes::Left) || collision.contains(&CollisionTypes::Right) {
-            map.player.velocity.x = 0.0;
+        if let Some(grab) = state.ledge_grab {
+            map.player = grab.hanging_player(map);
+            state.crouching = false;
         }
+    }
+
+    // if any of the jump keys are pressed, start jump buffer (up keys
+    // also jump unless they would climb a ladder or vine, and nothing
+    // jumps while the player is climbing up from a ledge)
+    let jump_pressed =
+        !was_hanging && (input.jump_pressed || (!volume_effects.climbable && input.up_pressed));
+
+    if jump_pressed {
+        state.jump_buffer = JUMP_BUFFER_HUNDREDTH_SECONDS;
+    }
 
-        // if any of the space keys are pressed, start jump buffer
-        let jump_pressed = window.is_key_pressed(Key::Space, KeyRepeat::No)
-            || window.is_key_pressed(Key::W, KeyRepeat::No)
-            || window.is_key_pressed(Key::Up, KeyRepeat::No);
+    // handle swimming (the player can keep swimming up as long as they're in water)
+    if volume_effects.in_water && state.jump_buffer > 0.0 {
+        map.player.velocity.y = SWIM_STROKE_FORCE * volume_effects.gravity_direction;
+        state.jump_buffer = 0.0;
+        state.carrier = None;
+    }
+    // jumping lets go of ladders and vines
+    else if state.climbing && state.jump_buffer > 0.0 {
+        map.player.velocity.y = JUMP_FORCE * volume_effects.gravity_direction;
+        state.jump_buffer = 0.0;
+        state.climbing = false;
+    }
+    // handle jumping
+    else if state.coyote_time > 0.0 && state.jump_buffer > 0.0 {
+        // reset coyote time
+        state.coyote_time = 0.0;
 
-        if jump_pressed {
-            jump_buffer = JUMP_BUFFER_HUNDREDTH_SECONDS;
+        // set the correct vertical velocity
+        map.player.velocity.y = JUMP_FORCE * volume_effects.gravity_direction;
+
+        // jumping off of something moving keeps its momentum
+        if let Some(current) = state.carrier {
+            carrier_velocity(map, current, tick_time).add_to(&mut map.player.velocity);
         }
 
-        // handle jumping
-        if coyote_time > 0.0 && jump_buffer > 0.0 {
-            // reset coyote time
-            coyote_time = 0.0;
+        // reset the jump buffer
+        state.jump_buffer = 0.0;
 
-            // if the player is stuck to a platform, add that object's
-            // velocity multiplied by a constant to the player's velocity
-            let mut additional_velocity = Vector2::new(0.0, 0.0);
-            if let Some(obj) = stuck_platform {
-                additional_velocity =
-                    Vector2::multiply(&obj.prev_move(), STUCK_PLATFORM_VELOCITY_ADD_MODIFIER);
+        // the player isn't standing on anything anymore
+        state.carrier = None;
+    }
+    // if the player is on the top of or the bottom of an
+    // object, reset the player's vertical velocity
+    else if state.collision.contains(&CollisionTypes::Top)
+        || state.collision.contains(&CollisionTypes::Bottom)
+    {
+        map.player.velocity.y =
+            VERTICAL_VELOCITY_ON_OR_UNDER_OBJECT * volume_effects.gravity_direction;
+    }
+
+    // teleporters only work again once the player is off of the end they came out of
+    if let Some((teleporter, end)) = state.teleport_exit {
+        if !map.teleporters[teleporter].ends[end].intersects_rigidbody(&map.player) {
+            state.teleport_exit = None;
+        }
+    }
+
+    // send the player through any teleporter they're touching
+    state.teleport_cooldown -= tick_time;
+    if state.teleport_cooldown <= 0.0 && state.teleport_exit.is_none() {
+        for (index, teleporter) in map.teleporters.iter().enumerate() {
+            if let Some(end) = teleporter.destination(&map.player) {
+                map.player.center = teleporter.ends[end].center();
+                map.player.velocity = teleporter.exit_velocity(&map.player.velocity);
+
+                state.teleport_cooldown = TELEPORT_COOLDOWN_HUNDREDTH_SECONDS;
+                state.teleport_exit = Some((index, end));
+                state.carrier = None;
+                state.ledge_grab = None;
+
+                // don't make the camera ease across the whole level
+                events.teleported = true;
+                break;
             }
+        }
+    }
 
-            // set the correct vertical velocity
-            map.player.velocity.y = JUMP_FORCE;
-            additional_velocity.add_to(&mut map.player.velocity);
+    // pick up keys
+    for door_key in &mut map.door_keys {
+        if !door_key.collected && door_key.indicator.intersects_rigidbody(&map.player) {
+            door_key.collected = true;
+            state.inventory.keys.push(door_key.key);
+        }
+    }
 
-            // reset the jump buffer
-            jump_buffer = 0.0;
+    // pick up gems
+    for gem in &mut map.gems {
+        if !gem.collected && gem.indicator.intersects_rigidbody(&map.player) {
+            gem.collected = true;
+        }
+    }
 
-            // unstick the player from the platform
-            stuck_platform = None;
+    // handle checkpoints
+    let mut reached_checkpoint = false;
+    for checkpoint in &map.checkpoints {
+        if checkpoint.indicator.intersects_rigidbody(&map.player) {
+            map.player_respawn = checkpoint.respawn;
+            reached_checkpoint = true;
         }
-        // if the player is on the top of or the bottom of an
-        // object, reset the player's vertical velocity
-        else if collision.contains(&CollisionTypes::Top)
-            || collision.contains(&CollisionTypes::Bottom)
+    }
+
+    if reached_checkpoint {
+        map.restore_health();
+        map.save_checkpoint_state();
+        state.checkpoint_inventory = state.inventory.clone();
+    }
+
+    // hazards hurt the player unless they were hurt recently
+    map.invulnerability -= tick_time;
+    let mut killed = false;
+
+    if map.invulnerability <= 0.0 {
+        let mut hit = map
+            .hazards
+            .iter()
+            .find(|hazard| hazard.circle.intersects_rigidbody(&map.player))
+            .map(|hazard| (hazard.damage(), hazard.circle.center()));
+
+        // projectiles are destroyed when they hit the player
+        if let Some(index) = map
+            .projectiles
+            .iter()
+            .position(|projectile| projectile.circle.intersects_rigidbody(&map.player))
         {
-            map.player.velocity.y = VERTICAL_VELOCITY_ON_OR_UNDER_OBJECT;
+            let projectile = map.projectiles.remove(index);
+            hit = hit.or(Some((PROJECTILE_DAMAGE, projectile.circle.center())));
         }
 
-        // handle checkpoints
-        for checkpoint in &map.checkpoints {
-            if checkpoint.indicator.intersects_rigidbody(&map.player) {
-                map.player_respawn = checkpoint.respawn;
+        if let Some((damage, source)) = hit {
+            killed = map.hurt_player(damage, &source);
+
+            // getting knocked back lets go of whatever the player was holding
+            state.ledge_grab = None;
+            state.climbing = false;
+            state.carrier = None;
+        }
+    }
+
+    // check if the player ended up inside of something after their collisions
+    let crush = map.find_crush(&map.player).filter(|crush| crush.is_fatal());
+
+    // respawn if the player is too low, out of health, or is being crushed
+    if killed || map.player.center.y < map.lowest_point || crush.is_some() {
+        // say what crushed the player in debug builds to help track down level bugs
+        if let (true, Some(crush)) = (cfg!(debug_assertions), crush) {
+            eprintln!("player was crushed: {}", crush);
+        }
+
+        respawn(map, state);
+        events.died = true;
+    }
+
+    // go to the next level if the goal was reached
+    events.reached_goal = map.goal.intersects_rigidbody(&map.player);
+
+    events
+}
+
+/// how a level ended
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LevelOutcome {
+    /// the player reached the goal
+    Completed,
+    /// the player wants to start the level over
+    Restarted,
+    /// the player wants to go back to the title menu
+    QuitToMenu,
+    /// the player closed the game
+    Quit,
+}
+
+/// what happened while a level was being played
+pub struct LevelResult {
+    pub outcome: LevelOutcome,
+    /// how many simulation ticks the level took
+    pub ticks: u64,
+    /// how many times the player died
+    pub deaths: u64,
+    /// which of the level's gems the player was holding at the end
+    pub gems: Vec<bool>,
+    /// where the player was on every tick
+    pub ghost: Ghost,
+}
+
+/// what a level needs to know when it's played as part of a time trial
+pub struct TimeTrial<'a> {
+    /// how long the earlier levels of the run took
+    pub run_ticks: u64,
+    /// the personal best run through this level to race against
+    pub ghost: Option<&'a Ghost>,
+}
+
+/**
+  plays a game with a supplied map and window
+* function will end when the player beats the level, closes the
+  window, or restarts or quits the level from the pause menu
+* the run timer and ghost are only shown in time trials
+* returns how the level ended, how long it took, how many times
+  the player died, which gems they got and where the player went
+*/
+pub fn play_game(
+    map: &mut Map,
+    window: &mut Window,
+    time_trial: Option<&TimeTrial>,
+) -> LevelResult {
+    // this will be where we write out pixel values
+    let mut window_buffer: Vec<u32> = vec![0; WINDOW_WIDTH * WINDOW_HEIGHT];
+
+    // create our camera
+    let mut camera = Camera::new(map.player.center.x - WINDOW_WIDTH as f64 / 2.0, 0.0);
+
+    // how long each frame takes (in hundreds of a seconds)
+    let mut frame_time: f64 = 0.0;
+
+    // the state of the player between ticks
+    let mut state = PlayerState::new();
+
+    // how much time hasn't been simulated yet, and how many ticks have been
+    let mut unsimulated_time: f64 = 0.0;
+    let mut ticks: u64 = 0;
+    let mut deaths: u64 = 0;
+
+    // where the player is every tick, for racing against later
+    let mut recording = Ghost::new();
+
+    // key presses from frames too short to simulate a tick in
+    let mut unused_input = Input::default();
+
+    // the loop only stops on its own when the window is closed
+    let mut outcome = LevelOutcome::Quit;
+
+    while window.is_open() {
+        // used to measure the frame time
+        let frame_start = std::time::Instant::now();
+
+        // pause over the last frame, carrying on afterwards unless
+        // the player picked something that ends the level
+        if any_key_pressed(window, &PAUSE_KEYS) {
+            let ended = match pause_menu(window, &window_buffer) {
+                Some(MenuItem::RestartLevel) => Some(LevelOutcome::Restarted),
+                Some(MenuItem::QuitToMenu) => Some(LevelOutcome::QuitToMenu),
+                Some(MenuItem::RestartFromCheckpoint) => {
+                    respawn(map, &mut state);
+                    camera.snap_to_player(&map.player);
+                    None
+                }
+                _ => None,
+            };
+
+            if let Some(ended) = ended {
+                outcome = ended;
+                break;
             }
+
+            // the time spent paused doesn't count
+            frame_time = 0.0;
+            unused_input = Input::default();
+            continue;
         }
 
-        // respawn if the player is too low or is being squished
-        if map.player.center.y < map.lowest_point
-            || (collision.contains(&CollisionTypes::Top)
-                && collision.contains(&CollisionTypes::Bottom))
-            || (collision.contains(&CollisionTypes::Left)
-                && collision.contains(&CollisionTypes::Right))
-        {
-            map.player = map.player_respawn;
+        //
+        // simulation
+        //
+
+        let mut input = Input::read(window).with_presses_from(&unused_input);
+
+        // simulate the game in fixed ticks so it doesn't depend on
+        // how fast frames render (slow frames only catch up so far)
+        unsimulated_time = f64::min(
+            unsimulated_time + frame_time,
+            SIMULATION_TICK_HUNDREDTH_SECONDS * MAX_TICKS_PER_FRAME as f64,
+        );
+
+        let mut reached_goal = false;
+        let mut simulated = false;
+
+        while unsimulated_time >= SIMULATION_TICK_HUNDREDTH_SECONDS && !reached_goal {
+            unsimulated_time -= SIMULATION_TICK_HUNDREDTH_SECONDS;
+            ticks += 1;
+
+            let events = simulate_tick(map, &mut state, &input, SIMULATION_TICK_HUNDREDTH_SECONDS);
+
+            if events.teleported {
+                camera.snap_to_player(&map.player);
+            }
+
+            if events.died {
+                deaths += 1;
+            }
+
+            reached_goal = events.reached_goal;
+            simulated = true;
+            input = input.held_only();
+
+            recording.record(&map.player);
         }
 
+        // move the ghost to where it was at this point in its run
+        // (its first frame was recorded after the first tick)
+        map.ghost = time_trial
+            .and_then(|trial| trial.ghost)
+            .zip(ticks.checked_sub(1))
+            .and_then(|(ghost, tick)| ghost.at_tick(tick));
+
+        unused_input = match simulated {
+            true => Input::default(),
+            false => input,
+        };
+
         // keep camera centered on player
         camera.keep_centered_on_player(&mut map.player, frame_time);
 
@@ -302,6 +1274,16 @@ pub fn play_game(map: &mut Map, window: &mut Window) -> bool {
 
         // render our graphics
         camera.render_frame(&render_game, map, &mut window_buffer);
+        render_hud(
+            map,
+            &HudStats {
+                keys: &state.inventory.keys,
+                ticks,
+                run_ticks: time_trial.map(|trial| trial.run_ticks + ticks),
+                deaths,
+            },
+            &mut window_buffer,
+        );
 
         // update our window with our pixel values
         window
@@ -314,20 +1296,17 @@ pub fn play_game(map: &mut Map, window: &mut Window) -> bool {
         frame_time = frame_start.elapsed().as_micros() as f64 / 10000.0;
 
         // go to the next level if the goal was reached
-        if map.goal.intersects_rigidbody(&map.player) {
+        if reached_goal {
+            outcome = LevelOutcome::Completed;
             break;
         }
     }
 
-    // update window with the last rendered frame so that
-    // any keys pressed last frame don't count as pressed
-    // next time they're read with key-repeat true
-    window
-        .update_with_buffer(&window_buffer, WINDOW_WIDTH, WINDOW_HEIGHT)
-        .unwrap_or_else(|error| {
-            panic!("Error updating window: {}", error);
-        });
-
-    // returns if the entire program was set to be terminated
-    !window.is_open() || window.is_key_down(Key::Escape)
+    LevelResult {
+        outcome,
+        ticks,
+        deaths,
+        gems: map.gems.iter().map(|gem| gem.collected).collect(),
+        ghost: recording,
+    }
 }
@@ -0,0 +1,153 @@
+use super::{
+    camera::Rgb,
+    constants::{
+        GEM_COLOR, HEALTH_COLOR, HEALTH_LOST_COLOR, HUD_ICON_SIZE, HUD_MARGIN, HUD_SHADOW_COLOR,
+        HUD_TEXT_COLOR, HUD_TEXT_SCALE, TIMER_COLOR, TIMER_TEXT_SCALE, WINDOW_HEIGHT, WINDOW_WIDTH,
+    },
+    font::{render_text, text_height, text_width},
+    map::{key_color, Map},
+    records::ticks_to_hundredths,
+};
+
+/// what the hud shows that isn't kept in the map
+pub struct HudStats<'a> {
+    /// the keys the player is carrying
+    pub keys: &'a [u32],
+    /// how many ticks the level has taken so far
+    pub ticks: u64,
+    /// how many ticks the whole run has taken, in time trials
+    pub run_ticks: Option<u64>,
+    /// how many times the player has died in the level
+    pub deaths: u64,
+}
+
+/// fills a rectangle of the screen with a color
+pub fn fill_rect(
+    buffer: &mut [u32],
+    left: usize,
+    top: usize,
+    width: usize,
+    height: usize,
+    color: u32,
+) {
+    for y in top..usize::min(top + height, WINDOW_HEIGHT) {
+        for x in left..usize::min(left + width, WINDOW_WIDTH) {
+            buffer[y * WINDOW_WIDTH + x] = color;
+        }
+    }
+}
+
+// draws text with a shadow behind it so it can be read over anything in the level
+fn render_shadowed_text(
+    text: &str,
+    left: usize,
+    top: usize,
+    scale: usize,
+    color: Rgb,
+    buffer: &mut [u32],
+) {
+    render_text(text, left + 1, top + 1, scale, HUD_SHADOW_COLOR, buffer);
+    render_text(text, left, top, scale, color, buffer);
+}
+
+// formats a number of ticks as minutes:seconds.hundredths
+fn format_time(ticks: u64) -> String {
+    let hundredths = ticks_to_hundredths(ticks);
+
+    format!(
+        "{}:{:02}.{:02}",
+        hundredths / 6000,
+        hundredths / 100 % 60,
+        hundredths % 100
+    )
+}
+
+// draws the keys the player is carrying in a row on the left of the screen
+fn render_keys(keys: &[u32], top: usize, buffer: &mut [u32]) {
+    for (slot, key) in keys.iter().enumerate() {
+        let left = HUD_MARGIN + slot * (HUD_ICON_SIZE + HUD_MARGIN / 2);
+
+        fill_rect(
+            buffer,
+            left,
+            top,
+            HUD_ICON_SIZE,
+            HUD_ICON_SIZE,
+            key_color(*key).to_u32(),
+        );
+    }
+}
+
+// draws the player's health in the top right corner in levels with health
+fn render_health(map: &Map, buffer: &mut [u32]) {
+    let Some(max_health) = map.max_health else {
+        return;
+    };
+
+    for slot in 0..max_health as usize {
+        let right = WINDOW_WIDTH - HUD_MARGIN - slot * (HUD_ICON_SIZE + HUD_MARGIN / 2);
+        let color = match slot < map.health as usize {
+            true => HEALTH_COLOR,
+            false => HEALTH_LOST_COLOR,
+        };
+
+        fill_rect(
+            buffer,
+            right.saturating_sub(HUD_ICON_SIZE),
+            HUD_MARGIN,
+            HUD_ICON_SIZE,
+            HUD_ICON_SIZE,
+            color.to_u32(),
+        );
+    }
+}
+
+/**
+  draws the hud over the game in screen space
+* the level's name is in the top left, with the keys the player has under it
+* the level timer is in the middle of the top, with the run timer under it in time trials
+* the player's health is in the top right in levels with health
+* how many times the player has died and how many gems they have are along the bottom
+*/
+pub fn render_hud(map: &Map, stats: &HudStats, buffer: &mut [u32]) {
+    let line_height = text_height(HUD_TEXT_SCALE);
+
+    render_shadowed_text(
+        map.name,
+        HUD_MARGIN,
+        HUD_MARGIN,
+        HUD_TEXT_SCALE,
+        HUD_TEXT_COLOR,
+        buffer,
+    );
+    render_keys(stats.keys, HUD_MARGIN * 2 + line_height, buffer);
+    render_health(map, buffer);
+
+    let mut top = HUD_MARGIN;
+    for ticks in [Some(stats.ticks), stats.run_ticks].into_iter().flatten() {
+        let text = format_time(ticks);
+        let left = (WINDOW_WIDTH - text_width(&text, TIMER_TEXT_SCALE)) / 2;
+
+        render_shadowed_text(&text, left, top, TIMER_TEXT_SCALE, TIMER_COLOR, buffer);
+        top += text_height(TIMER_TEXT_SCALE) + HUD_MARGIN / 2;
+    }
+
+    let bottom = WINDOW_HEIGHT - HUD_MARGIN - line_height;
+
+    render_shadowed_text(
+        &format!("Deaths {}", stats.deaths),
+        HUD_MARGIN,
+        bottom,
+        HUD_TEXT_SCALE,
+        HUD_TEXT_COLOR,
+        buffer,
+    );
+
+    if !map.gems.is_empty() {
+        let collected = map.gems.iter().filter(|gem| gem.collected).count();
+        let text = format!("Gems {}/{}", collected, map.gems.len());
+        let left = WINDOW_WIDTH - HUD_MARGIN - text_width(&text, HUD_TEXT_SCALE);
+
+        render_shadowed_text(&text, left, bottom, HUD_TEXT_SCALE, GEM_COLOR, buffer);
+    }
+}
@@ -15,7 +15,10 @@ use minifb::{Window, WindowOptions};
 mod objects;
 
 mod camera;
-use constants::{FRAME_LIMIT_MILLIS, WINDOW_HEIGHT, WINDOW_WIDTH};
+use constants::{
+    EXPORT_SPLITS_ARGUMENT, FRAME_LIMIT_MILLIS, IMPORT_SPLITS_ARGUMENT, LEVEL_COUNT,
+    TIME_TRIAL_ARGUMENT, WINDOW_HEIGHT, WINDOW_WIDTH,
+};
 
 mod map;
 use map::Map;
@@ -23,20 +26,58 @@ use map::Map;
 mod constants;
 
 mod game_player;
-use game_player::play_game;
+use game_player::{play_game, LevelOutcome, TimeTrial};
+
+mod records;
+use records::{Attempt, Ghost};
+
+mod save;
+use save::SaveData;
+
+mod splits;
+
+mod font;
+
+mod hud;
+
+mod menu;
+use menu::{level_select, settings_menu, title_menu, MenuItem};
 
 //
 // main
 //
 
+// returns the value given after an argument (like the path in --argument path)
+fn argument_value(arguments: &[String], name: &str) -> Option<String> {
+    let index = arguments.iter().position(|argument| argument == name)?;
+    arguments.get(index + 1).cloned()
+}
+
 fn main() {
-    let mut map: Map = Map::new();
-    let mut current_level = 1;
-    map.load_map(current_level);
+    let arguments: Vec<String> = std::env::args().collect();
+    let mut save = SaveData::load();
+
+    // export or import time trial splits instead of playing if asked to
+    if let Some(path) = argument_value(&arguments, EXPORT_SPLITS_ARGUMENT) {
+        splits::export(&save.records, &path).unwrap_or_else(|error| {
+            panic!("Error exporting splits: {}", error);
+        });
+        return;
+    }
+
+    if let Some(path) = argument_value(&arguments, IMPORT_SPLITS_ARGUMENT) {
+        splits::import(&mut save.records, &path).unwrap_or_else(|error| {
+            panic!("Error importing splits: {}", error);
+        });
+        save.save().unwrap_or_else(|error| {
+            panic!("Error saving: {}", error);
+        });
+        return;
+    }
 
     // our window :)
     let mut window = Window::new(
-        "Platformer - ESC to exit",
+        "Platformer - ESC to pause",
         WINDOW_WIDTH,
         WINDOW_HEIGHT,
         WindowOptions::default(),
@@ -49,8 +90,132 @@ fn main() {
     window.limit_update_rate(Some(std::time::Duration::from_millis(FRAME_LIMIT_MILLIS)));
     window.set_position(20, 20);
 
-    while !play_game(&mut map, &mut window) && current_level != 7 {
+    // time trials time every level and the whole run, keeping personal bests
+    let time_trial_argument = arguments
+        .iter()
+        .any(|argument| argument == TIME_TRIAL_ARGUMENT);
+
+    let mut map: Map = Map::new();
+
+    loop {
+        let first_level = match title_menu(&mut window, &save) {
+            Some(MenuItem::Play) => 1,
+            Some(MenuItem::Continue) => save.unlocked_level,
+            Some(MenuItem::LevelSelect) => match level_select(&mut window, &save) {
+                Some(level) => level,
+                None => continue,
+            },
+            Some(MenuItem::Settings) => {
+                settings_menu(&mut window, &mut save.settings);
+                save.save().unwrap_or_else(|error| {
+                    eprintln!("Error saving: {}", error);
+                });
+                continue;
+            }
+            _ => break,
+        };
+
+        let time_trial = time_trial_argument || save.settings.time_trials;
+        if play_levels(&mut map, &mut window, &mut save, first_level, time_trial) {
+            break;
+        }
+    }
+}
+
+/**
+  plays the levels in order from the first one given, keeping track
+  of the player's progress and time trial records in the save
+* stops when the last level is beaten or the player quits
+* returns if the player closed the game
+*/
+fn play_levels(
+    map: &mut Map,
+    window: &mut Window,
+    save: &mut SaveData,
+    first_level: u32,
+    time_trial: bool,
+) -> bool {
+    let mut current_level = first_level;
+    map.load_map(current_level);
+
+    let mut splits: Vec<u64> = Vec::new();
+
+    // runs only count if they start from the first level without restarting any levels
+    let mut full_run = first_level == 1;
+
+    // the times of the levels finished before the run stopped counting
+    let mut attempt = Attempt {
+        splits: Vec::new(),
+        completed: false,
+    };
+
+    let closed = loop {
+        // race against the ghost of the level's fastest time
+        let ghost = (time_trial && save.settings.race_ghosts)
+            .then(|| Ghost::load(&save.ghost_directory(), current_level))
+            .flatten();
+        let trial = TimeTrial {
+            run_ticks: splits.iter().sum(),
+            ghost: ghost.as_ref(),
+        };
+
+        let result = play_game(map, window, time_trial.then_some(&trial));
+        save.progress_mut(current_level).deaths += result.deaths;
+
+        match result.outcome {
+            LevelOutcome::Quit => break true,
+            LevelOutcome::QuitToMenu => break false,
+            LevelOutcome::Restarted => {
+                full_run = false;
+                map.load_map(current_level);
+                continue;
+            }
+            LevelOutcome::Completed => (),
+        }
+
+        save.complete_level(current_level, &result.gems);
+
+        if full_run {
+            attempt.splits.push(result.ticks);
+            attempt.completed = current_level == LEVEL_COUNT;
+        }
+
+        if time_trial && save.records.record_split(current_level, result.ticks) {
+            result
+                .ghost
+                .save(&save.ghost_directory(), current_level)
+                .unwrap_or_else(|error| {
+                    eprintln!("Error saving ghost: {}", error);
+                });
+        }
+
+        splits.push(result.ticks);
+
+        if time_trial && current_level == LEVEL_COUNT && full_run {
+            save.records.record_run(&splits);
+        }
+
+        save.save().unwrap_or_else(|error| {
+            eprintln!("Error saving: {}", error);
+        });
+
+        if current_level == LEVEL_COUNT {
+            break false;
+        }
+
         current_level += 1;
         map.load_map(current_level);
+    };
+
+    // keep the run in the history, even if it was given up on
+    if time_trial && !attempt.splits.is_empty() {
+        save.records.attempts.push(attempt);
     }
+
+    // keep the deaths from a level that was quit partway through
+    save.save().unwrap_or_else(|error| {
+        eprintln!("Error saving: {}", error);
+    });
+
+    closed
 }
@@ -1,12 +1,28 @@
+use std::{f64::consts::PI, fmt};
+
 use super::{
     camera::Rgb,
     constants::{
-        CHECKPOINT_COLOR, GOAL_COLOR, MOVING_PLATFORM_INDICATOR_COLOR,
+        BUMPER_COLOR, CHECKPOINT_COLOR, CRUSH_CONTACT_DISTANCE, CRUSH_OVERLAP_TOLERANCE, GEM_COLOR,
+        GEM_RADIUS, GOAL_COLOR, GRAPPLE_ANCHOR_COLOR, GRAPPLE_RANGE, HAZARD_COLOR,
+        INVULNERABILITY_HUNDREDTH_SECONDS, KEY_COLORS, KNOCKBACK_HORIZONTAL_SPEED,
+        KNOCKBACK_VERTICAL_SPEED, MOVING_PLATFORM_INDICATOR_COLOR,
         MOVING_PLATFORM_INDICATOR_RADIUS, PLAYER_HEIGHT, PLAYER_WIDTH,
+        PROJECTILE_LIFETIME_HUNDREDTH_SECONDS, ROUND_OBJECT_COLOR, SLOPE_SNAP_DISTANCE,
+        TELEPORTER_COLOR, TURRET_COLOR, WATER_DRAG, WATER_GRAVITY_SCALE,
+    },
+    objects::{
+        Activation, Circle, CollisionTypes, Door, Hazard, LineSegment, MovingObject, Orbit,
+        Projectile, RectObject, RigidBody, RoundObject, Slope, StaticObject, Switch, Turret,
+        TurretAim, Vector2, Volume, VolumeEffect,
     },
-    objects::{Circle, MovingObject, RigidBody, StaticObject, Vector2},
 };
 
+/// returns the color used to draw a key and the doors it opens
+pub fn key_color(key: u32) -> Rgb {
+    KEY_COLORS[key as usize % KEY_COLORS.len()]
+}
+
 pub struct Checkpoint {
     pub indicator: Circle,
     pub respawn: RigidBody,
@@ -26,15 +42,312 @@ impl Checkpoint {
     }
 }
 
+/// creates a chain of hazards spinning around a pivot, with the
+/// first one a spacing away from the pivot and each one after
+/// that another spacing further out
+pub fn fireball_bar(
+    pivot: Vector2,
+    count: u32,
+    spacing: f64,
+    radius: f64,
+    angular_speed: f64,
+    start_angle: f64,
+) -> Vec<Hazard> {
+    (1..=count)
+        .map(|link| {
+            Hazard::new_orbit(
+                Orbit::new(pivot, spacing * link as f64, angular_speed, start_angle),
+                radius,
+                HAZARD_COLOR,
+            )
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct DoorKey {
+    pub indicator: Circle,
+    pub key: u32,
+    pub collected: bool,
+}
+
+impl DoorKey {
+    pub fn new(center: Vector2, radius: f64, key: u32) -> DoorKey {
+        DoorKey {
+            indicator: Circle::new(&center, radius, key_color(key)),
+            key,
+            collected: false,
+        }
+    }
+}
+
+/// an optional collectible, which stays collected between runs once the level is beaten
+#[derive(Clone)]
+pub struct Gem {
+    pub indicator: Circle,
+    pub collected: bool,
+}
+
+impl Gem {
+    pub fn new(center: Vector2) -> Gem {
+        Gem {
+            indicator: Circle::new(&center, GEM_RADIUS, GEM_COLOR),
+            collected: false,
+        }
+    }
+}
+
+/// how the player's velocity changes when they go through a teleporter
+#[derive(Clone, Copy)]
+pub enum TeleportVelocity {
+    Keep,
+    /// rotates the velocity counterclockwise by the angle (in radians)
+    Rotate(f64),
+    Reset,
+}
+
+/// two circles that each send the player to the other
+pub struct Teleporter {
+    pub ends: [Circle; 2],
+    pub velocity: TeleportVelocity,
+}
+
+impl Teleporter {
+    pub fn new(
+        first: Vector2,
+        second: Vector2,
+        radius: f64,
+        velocity: TeleportVelocity,
+    ) -> Teleporter {
+        Teleporter {
+            ends: [
+                Circle::new(&first, radius, TELEPORTER_COLOR),
+                Circle::new(&second, radius, TELEPORTER_COLOR),
+            ],
+            velocity,
+        }
+    }
+
+    /// returns which end the player should be sent to if they're touching the other one
+    pub fn destination(&self, player: &RigidBody) -> Option<usize> {
+        if self.ends[0].intersects_rigidbody(player) {
+            Some(1)
+        } else if self.ends[1].intersects_rigidbody(player) {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// returns the velocity the player should leave the teleporter with
+    pub fn exit_velocity(&self, velocity: &Vector2) -> Vector2 {
+        match self.velocity {
+            TeleportVelocity::Keep => *velocity,
+            TeleportVelocity::Rotate(angle) => Vector2::rotate(velocity, angle),
+            TeleportVelocity::Reset => Vector2::new(0.0, 0.0),
+        }
+    }
+}
+
+/// the combined effect of all the volumes at a point
+pub struct VolumeEffects {
+    pub wind: Vector2,
+    pub gravity_scale: f64,
+    /// 1 when gravity pulls down and -1 when it pulls up
+    pub gravity_direction: f64,
+    /// how much of the player's velocity is taken away each hundredth of a second
+    pub drag: f64,
+    pub in_water: bool,
+    pub climbable: bool,
+}
+
+impl VolumeEffects {
+    /// returns the collision type that means the player is on the ground
+    /// (the surface opposite the direction gravity pulls)
+    pub fn ground(&self) -> CollisionTypes {
+        match self.gravity_direction > 0.0 {
+            true => CollisionTypes::Top,
+            false => CollisionTypes::Bottom,
+        }
+    }
+}
+
+/// a rope connecting the player to a grapple anchor
+#[derive(Clone, Copy)]
+pub struct Rope {
+    pub anchor: Vector2,
+    pub length: f64,
+}
+
+impl Rope {
+    /// keeps the rigidbody from going further from the anchor than the rope's
+    /// length, removing any velocity that would stretch the rope
+    pub fn constrain(&self, rigidbody: &mut RigidBody) {
+        let offset = Vector2::subtract(&rigidbody.center, &self.anchor);
+        let distance = Vector2::magnitude(&offset);
+
+        // a slack rope doesn't do anything
+        if distance <= self.length || distance == 0.0 {
+            return;
+        }
+
+        // pull the rigidbody back to the end of the rope
+        let direction = Vector2::multiply(&offset, 1.0 / distance);
+        rigidbody.center = Vector2::add(&self.anchor, &Vector2::multiply(&direction, self.length));
+
+        // and only keep the velocity that swings around the anchor
+        let outward_speed = Vector2::dot(&rigidbody.velocity, &direction);
+        if outward_speed > 0.0 {
+            Vector2::multiply(&direction, -outward_speed).add_to(&mut rigidbody.velocity);
+        }
+    }
+}
+
+/// a box that falls, collides and can be pushed around by the player
+#[derive(Clone)]
+pub struct Crate {
+    pub body: RigidBody,
+    /// how the crate collided with things last frame
+    pub collision: Vec<CollisionTypes>,
+    /// what the crate was resting on last frame
+    pub carrier: Option<Carrier>,
+    /// how far the crate moved last frame (including being carried)
+    pub prev_move: Vector2,
+}
+
+impl Crate {
+    pub fn new(center: Vector2, size: f64) -> Crate {
+        Crate {
+            body: RigidBody {
+                center,
+                width: size,
+                height: size,
+                velocity: Vector2::new(0.0, 0.0),
+            },
+            collision: Vec::new(),
+            carrier: None,
+            prev_move: Vector2::new(0.0, 0.0),
+        }
+    }
+}
+
+impl RectObject for Crate {
+    fn center(&self) -> Vector2 {
+        self.body.center
+    }
+
+    fn width(&self) -> f64 {
+        self.body.width
+    }
+
+    fn height(&self) -> f64 {
+        self.body.height
+    }
+}
+
+/// something that carries the bodies resting on it along as it moves
+#[derive(Clone, Copy)]
+pub enum Carrier {
+    MovingObject(usize),
+    Crate(usize),
+}
+
+/// a solid part of the level that doesn't move on its own
+#[derive(Clone, Copy)]
+pub enum Solid {
+    StaticObject(usize),
+    Door(usize),
+    Switch(usize),
+    RoundObject(usize),
+    Slope(usize),
+}
+
+/// why a body is still inside the level after all of its collisions were handled
+#[derive(Clone, Copy)]
+pub enum Crush {
+    /// the body is still inside a moving object
+    MovingObject(usize),
+    /// a moving object pushed the body into something solid
+    Pinned { moving_object: usize, solid: Solid },
+    /// the body is inside something solid with no moving object
+    /// involved, which is a collision bug rather than a crush
+    Clipping(Solid),
+}
+
+impl fmt::Display for Solid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Solid::StaticObject(index) => write!(f, "static object {}", index),
+            Solid::Door(index) => write!(f, "door {}", index),
+            Solid::Switch(index) => write!(f, "switch {}", index),
+            Solid::RoundObject(index) => write!(f, "round object {}", index),
+            Solid::Slope(index) => write!(f, "slope {}", index),
+        }
+    }
+}
+
+impl Crush {
+    /// returns if the crush should kill whatever is being crushed
+    pub fn is_fatal(&self) -> bool {
+        !matches!(self, Crush::Clipping(_))
+    }
+}
+
+impl fmt::Display for Crush {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Crush::MovingObject(index) => write!(f, "inside moving object {}", index),
+            Crush::Pinned {
+                moving_object,
+                solid,
+            } => write!(
+                f,
+                "pinned against {} by moving object {}",
+                solid, moving_object
+            ),
+            Crush::Clipping(solid) => write!(f, "clipping into {}", solid),
+        }
+    }
+}
+
+/// what a body was standing on after colliding with the level
+pub struct LevelContacts {
+    pub platform_on: Option<usize>,
+    pub switch_on: Option<usize>,
+}
+
 pub struct Map {
+    /// the level's name, which is shown in the hud
+    pub name: &'static str,
+
     pub static_objects: Vec<StaticObject>,
     pub moving_objects: Vec<MovingObject>,
+    pub switches: Vec<Switch>,
+    pub doors: Vec<Door>,
+    pub volumes: Vec<Volume>,
+    pub crates: Vec<Crate>,
+    pub round_objects: Vec<RoundObject>,
+    pub slopes: Vec<Slope>,
 
     // circles
+    pub hazards: Vec<Hazard>,
     pub moving_object_indicators: Vec<Circle>,
     pub checkpoints: Vec<Checkpoint>,
+    pub door_keys: Vec<DoorKey>,
+    pub gems: Vec<Gem>,
+    pub teleporters: Vec<Teleporter>,
+    pub grapple_anchors: Vec<Circle>,
+    pub turrets: Vec<Turret>,
+    pub projectiles: Vec<Projectile>,
     pub goal: Circle,
 
+    /// the doors, keys, gems and crates as they
+    /// were when the player last reached a checkpoint
+    checkpoint_doors: Vec<Door>,
+    checkpoint_door_keys: Vec<DoorKey>,
+    checkpoint_gems: Vec<Gem>,
+    checkpoint_crates: Vec<Crate>,
+
     /// the rigidbody the player will
     /// be set to when it respawns
     pub player_respawn: RigidBody,
@@ -42,24 +355,445 @@ pub struct Map {
     // the player
     pub player: RigidBody,
 
+    /// where the personal best run's ghost is, if it's being raced
+    pub ghost: Option<RigidBody>,
+
+    /// the rope the player is swinging on, if any
+    pub rope: Option<Rope>,
+
     /// if the player goes below this point, they respawn
     pub lowest_point: f64,
+
+    /// how long the level has been running (in hundredths of a second),
+    /// moving objects derive their positions from this
+    pub level_clock: f64,
+
+    /// if the level clock goes back to 0 when the player dies
+    pub reset_clock_on_death: bool,
+
+    /// how much health the player has in this level, or none
+    /// if anything that hurts the player kills them outright
+    pub max_health: Option<u32>,
+
+    /// the player's current health (unused in one-hit levels)
+    pub health: u32,
+
+    /// how long until the player can be hurt again (in hundredths of a second)
+    pub invulnerability: f64,
 }
 
 impl Map {
     pub fn new() -> Map {
         Map {
+            name: "",
+
             static_objects: Vec::new(),
             moving_objects: Vec::new(),
+            switches: Vec::new(),
+            doors: Vec::new(),
+            volumes: Vec::new(),
+            crates: Vec::new(),
+            round_objects: Vec::new(),
+            slopes: Vec::new(),
 
+            hazards: Vec::new(),
             moving_object_indicators: Vec::new(),
             checkpoints: Vec::new(),
+            door_keys: Vec::new(),
+            gems: Vec::new(),
+            teleporters: Vec::new(),
+            grapple_anchors: Vec::new(),
+            turrets: Vec::new(),
+            projectiles: Vec::new(),
             goal: Circle::new(&Vector2::new(0.0, 0.0), 0.0, Rgb::new(0, 0, 0)),
 
+            checkpoint_doors: Vec::new(),
+            checkpoint_door_keys: Vec::new(),
+            checkpoint_gems: Vec::new(),
+            checkpoint_crates: Vec::new(),
+
             player_respawn: RigidBody::new(),
             player: RigidBody::new(),
+            ghost: None,
+            rope: None,
 
             lowest_point: 0.0,
+
+            level_clock: 0.0,
+            reset_clock_on_death: false,
+
+            max_health: None,
+            health: 0,
+            invulnerability: 0.0,
+        }
+    }
+
+    /// puts the player back at their respawn point, resetting
+    /// the level clock if the level is set to do so
+    pub fn respawn_player(&mut self) {
+        self.player = self.player_respawn;
+        self.rope = None;
+        self.restore_health();
+
+        if self.reset_clock_on_death {
+            self.level_clock = 0.0;
+        }
+
+        // put all moving objects back where they should be
+        // and turn off anything the player activated
+        for moving_object in &mut self.moving_objects {
+            moving_object.reset(self.level_clock);
+        }
+
+        for hazard in &mut self.hazards {
+            hazard.update(self.level_clock);
+        }
+
+        for switch in &mut self.switches {
+            switch.reset();
+        }
+
+        // clear out anything that was fired at the player
+        self.projectiles.clear();
+        for turret in &mut self.turrets {
+            turret.reset();
+        }
+
+        // put doors, keys, gems and crates back how they were at the last checkpoint
+        self.doors = self.checkpoint_doors.clone();
+        self.door_keys = self.checkpoint_door_keys.clone();
+        self.gems = self.checkpoint_gems.clone();
+        self.crates = self.checkpoint_crates.clone();
+    }
+
+    /// gives the player back all of their health
+    pub fn restore_health(&mut self) {
+        self.health = self.max_health.unwrap_or(0);
+        self.invulnerability = 0.0;
+    }
+
+    /// takes health away from the player and knocks them away from the
+    /// source of the damage, returning if the hit should kill them
+    pub fn hurt_player(&mut self, damage: u32, source: &Vector2) -> bool {
+        if self.max_health.is_none() {
+            return true;
+        }
+
+        self.health = self.health.saturating_sub(damage);
+        if self.health == 0 {
+            return true;
+        }
+
+        self.invulnerability = INVULNERABILITY_HUNDREDTH_SECONDS;
+
+        let direction = match self.player.center.x < source.x {
+            true => -1.0,
+            false => 1.0,
+        };
+        self.player.velocity = Vector2::new(
+            direction * KNOCKBACK_HORIZONTAL_SPEED,
+            KNOCKBACK_VERTICAL_SPEED,
+        );
+        self.rope = None;
+
+        false
+    }
+
+    /// returns if a straight line between the two points
+    /// doesn't pass through any static objects or closed doors
+    pub fn line_of_sight(&self, from: &Vector2, to: &Vector2) -> bool {
+        let line = LineSegment::new(*from, *to);
+
+        !self
+            .static_objects
+            .iter()
+            .any(|object| line.intersects_bounds(&object.bounds()))
+            && !self
+                .doors
+                .iter()
+                .any(|door| !door.is_open() && line.intersects_bounds(&door.bounds()))
+    }
+
+    /// fires any turrets that are ready and moves all projectiles,
+    /// destroying the ones that hit something or flew too long
+    pub fn update_turrets(&mut self, frame_time: f64) {
+        for index in 0..self.turrets.len() {
+            let turret = &self.turrets[index];
+            let direction = match turret.aim {
+                TurretAim::Fixed(direction) => Some(direction),
+                TurretAim::AtPlayer { range } => {
+                    let to_player = Vector2::subtract(&self.player.center, &turret.circle.center());
+
+                    match Vector2::magnitude(&to_player) <= range
+                        && self.line_of_sight(&turret.circle.center(), &self.player.center)
+                    {
+                        true => Some(to_player),
+                        false => None,
+                    }
+                }
+            };
+
+            if let Some(projectile) = self.turrets[index].update(frame_time, direction) {
+                self.projectiles.push(projectile);
+            }
+        }
+
+        for projectile in &mut self.projectiles {
+            projectile.update(frame_time);
+        }
+
+        let mut projectiles = std::mem::take(&mut self.projectiles);
+        projectiles.retain(|projectile| {
+            let center = projectile.circle.center();
+
+            projectile.age() < PROJECTILE_LIFETIME_HUNDREDTH_SECONDS
+                && !self
+                    .static_objects
+                    .iter()
+                    .any(|object| object.contains_point(&center))
+                && !self
+                    .doors
+                    .iter()
+                    .any(|door| !door.is_open() && door.contains_point(&center))
+                && !self
+                    .slopes
+                    .iter()
+                    .any(|slope| slope.contains_point(&center))
+        });
+        self.projectiles = projectiles;
+    }
+
+    /// remembers the doors, keys, gems and crates so
+    /// they can be restored when the player respawns
+    pub fn save_checkpoint_state(&mut self) {
+        self.checkpoint_doors = self.doors.clone();
+        self.checkpoint_door_keys = self.door_keys.clone();
+        self.checkpoint_gems = self.gems.clone();
+        self.checkpoint_crates = self.crates.clone();
+    }
+
+    /// returns if a body overlaps any of the level's solid objects or crates
+    pub fn overlaps_solid(&self, body: &RigidBody) -> bool {
+        self.static_objects
+            .iter()
+            .any(|object| body.collides_with(object))
+            || self
+                .moving_objects
+                .iter()
+                .any(|object| body.collides_with(object))
+            || self
+                .doors
+                .iter()
+                .any(|door| !door.is_open() && body.collides_with(door))
+            || self
+                .switches
+                .iter()
+                .any(|switch| body.collides_with(switch))
+            || self
+                .crates
+                .iter()
+                .any(|current| body.collides_with(current))
+            || self
+                .round_objects
+                .iter()
+                .any(|object| object.contact(body).is_some())
+            || self.slopes.iter().any(|slope| slope.overlaps(body))
+    }
+
+    /// returns the solid the body is overlapping, if any
+    fn overlapping_solid(&self, body: &RigidBody) -> Option<Solid> {
+        let static_object = self
+            .static_objects
+            .iter()
+            .position(|object| body.collides_with(object))
+            .map(Solid::StaticObject);
+        let door = self
+            .doors
+            .iter()
+            .position(|door| !door.is_open() && body.collides_with(door))
+            .map(Solid::Door);
+        let switch = self
+            .switches
+            .iter()
+            .position(|switch| body.collides_with(switch))
+            .map(Solid::Switch);
+        let round_object = self
+            .round_objects
+            .iter()
+            .position(|object| object.contact(body).is_some())
+            .map(Solid::RoundObject);
+        let slope = self
+            .slopes
+            .iter()
+            .position(|slope| slope.overlaps(body))
+            .map(Solid::Slope);
+
+        static_object.or(door).or(switch).or(round_object).or(slope)
+    }
+
+    /// checks if a body that has had all of its collisions handled is
+    /// still inside the level, and returns what is crushing it if so
+    pub fn find_crush(&self, body: &RigidBody) -> Option<Crush> {
+        // ignore overlaps small enough to just be rounding errors
+        let inner = RigidBody {
+            width: body.width - CRUSH_OVERLAP_TOLERANCE * 2.0,
+            height: body.height - CRUSH_OVERLAP_TOLERANCE * 2.0,
+            ..*body
+        };
+
+        if let Some(index) = self
+            .moving_objects
+            .iter()
+            .position(|object| inner.collides_with(object))
+        {
+            return Some(Crush::MovingObject(index));
+        }
+
+        let solid = self.overlapping_solid(&inner)?;
+
+        // a moving object touching the body is what pushed it into the solid
+        let reach = RigidBody {
+            width: body.width + CRUSH_CONTACT_DISTANCE * 2.0,
+            height: body.height + CRUSH_CONTACT_DISTANCE * 2.0,
+            ..*body
+        };
+
+        match self
+            .moving_objects
+            .iter()
+            .position(|object| reach.collides_with(object))
+        {
+            Some(moving_object) => Some(Crush::Pinned {
+                moving_object,
+                solid,
+            }),
+            None => Some(Crush::Clipping(solid)),
+        }
+    }
+
+    /// moves a body down onto a slope just below it so it doesn't
+    /// bounce while running downhill, returning if it was moved
+    pub fn snap_to_slope(&self, body: &mut RigidBody) -> bool {
+        let bounds = body.bounds();
+
+        let surface = self
+            .slopes
+            .iter()
+            .filter_map(|slope| slope.highest_surface(bounds.left, bounds.right))
+            .filter(|surface| {
+                *surface <= bounds.bottom && bounds.bottom - surface <= SLOPE_SNAP_DISTANCE
+            })
+            .reduce(f64::max);
+
+        match surface {
+            Some(surface) => {
+                body.center.y = surface + body.height / 2.0;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// handles a body's collisions with all of the level's solid objects,
+    /// putting the collision types into collisions
+    pub fn collide_with_level(
+        &self,
+        body: &mut RigidBody,
+        collisions: &mut Vec<CollisionTypes>,
+        gravity_direction: f64,
+    ) -> LevelContacts {
+        let platform_on =
+            body.handle_collisions(&self.moving_objects, collisions, gravity_direction);
+
+        body.handle_collisions(&self.static_objects, collisions, gravity_direction);
+        body.handle_round_collisions(&self.round_objects, collisions);
+        body.handle_slope_collisions(&self.slopes, collisions, gravity_direction);
+
+        let closed_doors: Vec<Door> = self
+            .doors
+            .iter()
+            .filter(|door| !door.is_open())
+            .cloned()
+            .collect();
+        body.handle_collisions(&closed_doors, collisions, gravity_direction);
+
+        let switch_on = body.handle_collisions(&self.switches, collisions, gravity_direction);
+
+        LevelContacts {
+            platform_on,
+            switch_on,
+        }
+    }
+
+    /// returns the combined effect of every volume containing the point
+    pub fn volume_effects_at(&self, point: &Vector2) -> VolumeEffects {
+        let mut effects = VolumeEffects {
+            wind: Vector2::new(0.0, 0.0),
+            gravity_scale: 1.0,
+            gravity_direction: 1.0,
+            drag: 0.0,
+            in_water: false,
+            climbable: false,
+        };
+
+        for volume in &self.volumes {
+            if !volume.contains_point(point) {
+                continue;
+            }
+
+            match volume.effect {
+                VolumeEffect::Wind(force) => force.add_to(&mut effects.wind),
+                VolumeEffect::GravityScale(scale) => effects.gravity_scale *= scale,
+                VolumeEffect::InvertGravity => effects.gravity_direction = -1.0,
+                VolumeEffect::Water => {
+                    effects.gravity_scale *= WATER_GRAVITY_SCALE;
+                    effects.drag += WATER_DRAG;
+                    effects.in_water = true;
+                }
+                VolumeEffect::Climbable => effects.climbable = true,
+            }
+        }
+
+        effects
+    }
+
+    /// returns a rope to the closest grapple anchor in range of the player, if any
+    pub fn find_grapple(&self) -> Option<Rope> {
+        self.grapple_anchors
+            .iter()
+            .map(|anchor| Rope {
+                anchor: anchor.center(),
+                length: Vector2::magnitude(&Vector2::subtract(
+                    &self.player.center,
+                    &anchor.center(),
+                )),
+            })
+            .filter(|rope| rope.length <= GRAPPLE_RANGE)
+            .min_by(|rope1, rope2| rope1.length.total_cmp(&rope2.length))
+    }
+
+    /// tells every object with an id if a switch targeting it is on
+    pub fn apply_switches(&mut self) {
+        for moving_object in &mut self.moving_objects {
+            if let Some(id) = moving_object.id() {
+                let switched_on = self
+                    .switches
+                    .iter()
+                    .any(|switch| switch.on && switch.targets(id));
+
+                moving_object.set_switched_on(switched_on);
+            }
+        }
+
+        for door in &mut self.doors {
+            if let Some(id) = door.id() {
+                let switched_on = self
+                    .switches
+                    .iter()
+                    .any(|switch| switch.on && switch.targets(id));
+
+                door.set_switched_on(switched_on);
+            }
         }
     }
 
@@ -71,6 +805,8 @@ impl Map {
         // correct map in the this object
         match level {
             1 => {
+                self.name = "First Steps";
+
                 self.static_objects = vec![
                     StaticObject::new(Vector2::new(180.0, -520.0), 440.0, 1000.0),
                     StaticObject::new(Vector2::new(650.0, -520.0), 300.0, 1100.0),
@@ -86,10 +822,15 @@ impl Map {
 
                 self.goal = Circle::new(&Vector2::new(700.0, 100.0), 20.0, GOAL_COLOR);
 
+                // a gem to grab while jumping over the gap
+                self.gems = vec![Gem::new(Vector2::new(450.0, 50.0))];
+
                 self.lowest_point = -200.0;
             }
 
             2 => {
+                self.name = "All Aboard";
+
                 self.static_objects = vec![
                     StaticObject::new(Vector2::new(180.0, -520.0), 440.0, 1000.0),
                     StaticObject::new(Vector2::new(1125.0, -500.0), 440.0, 1000.0),
@@ -117,6 +858,8 @@ impl Map {
             }
 
             3 => {
+                self.name = "On the Move";
+
                 self.static_objects = vec![
                     StaticObject::new(Vector2::new(200.0, -500.0), 400.0, 1000.0),
                     StaticObject::new(Vector2::new(325.0, 340.0), 150.0, 100.0),
@@ -146,6 +889,8 @@ impl Map {
             }
 
             4 => {
+                self.name = "Stepping Stones";
+
                 self.static_objects = vec![
                     StaticObject::new(Vector2::new(100.0, -500.0), 400.0, 1000.0),
                     StaticObject::new(Vector2::new(480.0, 10.0), 100.0, 100.0),
@@ -184,6 +929,8 @@ impl Map {
             }
 
             5 => {
+                self.name = "The Long Way";
+
                 self.static_objects = vec![
                     StaticObject::new(Vector2::new(0.0, -800.0), 600.0, 1600.0),
                     StaticObject::new(Vector2::new(500.0, 300.0), 60.0, 600.0),
@@ -215,6 +962,8 @@ impl Map {
             }
 
             6 => {
+                self.name = "Checkpoints";
+
                 self.static_objects = vec![
                     StaticObject::new(Vector2::new(100.0, -520.0), 400.0, 1000.0),
                     StaticObject::new(Vector2::new(80.0, 310.0), 120.0, 100.0),
@@ -259,6 +1008,8 @@ impl Map {
             }
 
             7 => {
+                self.name = "Halfway There";
+
                 self.static_objects = vec![
                     StaticObject::new(Vector2::new(150.0, -520.0), 450.0, 1000.0),
                     StaticObject::new(Vector2::new(540.0, -10.0), 100.0, 80.0),
@@ -330,25 +1081,592 @@ impl Map {
                 self.lowest_point = -150.0;
             }
 
+            8 => {
+                self.name = "In Step";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(100.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(1250.0, -480.0), 400.0, 1000.0),
+                ];
+
+                // each platform is a quarter of a cycle behind the last
+                self.moving_objects = vec![
+                    MovingObject::new(
+                        Vector2::new(420.0, -60.0),
+                        Vector2::new(420.0, 60.0),
+                        100.0,
+                        25.0,
+                        120.0,
+                    ),
+                    MovingObject::new(
+                        Vector2::new(580.0, -60.0),
+                        Vector2::new(580.0, 60.0),
+                        100.0,
+                        25.0,
+                        120.0,
+                    )
+                    .with_phase_offset(0.5),
+                    MovingObject::new(
+                        Vector2::new(740.0, -60.0),
+                        Vector2::new(740.0, 60.0),
+                        100.0,
+                        25.0,
+                        120.0,
+                    )
+                    .with_phase_offset(1.0),
+                    MovingObject::new(
+                        Vector2::new(900.0, -60.0),
+                        Vector2::new(900.0, 60.0),
+                        100.0,
+                        25.0,
+                        120.0,
+                    )
+                    .with_phase_offset(1.5),
+                ];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(1350.0, 120.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -200.0;
+
+                // keep the platforms in the same pattern every attempt
+                self.reset_clock_on_death = true;
+            }
+
+            9 => {
+                self.name = "Flip the Switch";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(100.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(1200.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(1700.0, 250.0), 200.0, 100.0),
+                    StaticObject::new(Vector2::new(2400.0, 250.0), 200.0, 100.0),
+                ];
+
+                self.moving_objects = vec![
+                    // carries the player across the first gap once
+                    MovingObject::new(
+                        Vector2::new(420.0, -20.0),
+                        Vector2::new(900.0, -20.0),
+                        100.0,
+                        25.0,
+                        250.0,
+                    )
+                    .with_activation(Activation::OneShot),
+                    // lifts the player while they stand on it
+                    MovingObject::new(
+                        Vector2::new(1470.0, -12.5),
+                        Vector2::new(1470.0, 287.5),
+                        100.0,
+                        25.0,
+                        200.0,
+                    )
+                    .with_activation(Activation::WhileStoodOn),
+                    // only ferries the player once the switch is on
+                    MovingObject::new(
+                        Vector2::new(1900.0, 287.5),
+                        Vector2::new(2200.0, 287.5),
+                        100.0,
+                        25.0,
+                        150.0,
+                    )
+                    .with_id(1)
+                    .with_activation(Activation::Switched),
+                ];
+
+                self.switches = vec![Switch::new(Vector2::new(1100.0, 2.0), 60.0, 4.0, vec![1])];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(2400.0, 360.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -200.0;
+            }
+
+            10 => {
+                self.name = "Lock and Key";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(350.0, -500.0), 1100.0, 1000.0),
+                    StaticObject::new(Vector2::new(1400.0, -500.0), 800.0, 1000.0),
+                ];
+
+                self.doors = vec![
+                    Door::new(Vector2::new(600.0, 200.0), 40.0, 400.0).with_key(0),
+                    Door::new(Vector2::new(1250.0, 200.0), 40.0, 400.0).with_key(1),
+                    Door::new(Vector2::new(1550.0, 200.0), 40.0, 400.0).with_id(1),
+                ];
+
+                self.door_keys = vec![
+                    DoorKey::new(Vector2::new(300.0, 30.0), 12.0, 0),
+                    DoorKey::new(Vector2::new(1100.0, 30.0), 12.0, 1),
+                ];
+
+                self.switches = vec![Switch::new(Vector2::new(1400.0, 2.0), 60.0, 4.0, vec![1])];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.checkpoints = vec![Checkpoint::new(
+                    Circle::new(&Vector2::new(800.0, 40.0), 15.0, CHECKPOINT_COLOR),
+                    Vector2::new(800.0, PLAYER_HEIGHT / 2.0),
+                )];
+
+                self.goal = Circle::new(&Vector2::new(1700.0, 40.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -200.0;
+            }
+
+            11 => {
+                self.name = "Fireballs";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(100.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(1200.0, -450.0), 400.0, 1000.0),
+                ];
+
+                self.moving_objects = vec![
+                    MovingObject::new_orbit(
+                        Orbit::new(Vector2::new(500.0, 50.0), 110.0, 0.012, 0.0),
+                        100.0,
+                        25.0,
+                    ),
+                    MovingObject::new_orbit(
+                        Orbit::new(Vector2::new(800.0, 120.0), 110.0, -0.012, PI),
+                        100.0,
+                        25.0,
+                    ),
+                ];
+
+                self.hazards = fireball_bar(Vector2::new(1200.0, 150.0), 5, 20.0, 8.0, 0.02, 0.0);
+                self.hazards.push(Hazard::new(
+                    &Vector2::new(1200.0, 150.0),
+                    12.0,
+                    HAZARD_COLOR,
+                ));
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(1350.0, 100.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -200.0;
+            }
+
+            12 => {
+                self.name = "Teleporters";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(100.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(2000.0, 250.0), 300.0, 100.0),
+                    StaticObject::new(Vector2::new(3000.0, -450.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(4150.0, -400.0), 400.0, 1000.0),
+                ];
+
+                self.teleporters = vec![
+                    Teleporter::new(
+                        Vector2::new(250.0, 30.0),
+                        Vector2::new(1900.0, 330.0),
+                        20.0,
+                        TeleportVelocity::Reset,
+                    ),
+                    // turns running into a launch upward
+                    Teleporter::new(
+                        Vector2::new(2100.0, 330.0),
+                        Vector2::new(2950.0, 100.0),
+                        20.0,
+                        TeleportVelocity::Rotate(PI / 2.0),
+                    ),
+                    Teleporter::new(
+                        Vector2::new(3150.0, 80.0),
+                        Vector2::new(4000.0, 200.0),
+                        20.0,
+                        TeleportVelocity::Keep,
+                    ),
+                ];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(4250.0, 150.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -200.0;
+            }
+
+            13 => {
+                self.name = "Wind and Gravity";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(100.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(850.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(1400.0, 200.0), 400.0, 40.0),
+                    StaticObject::new(Vector2::new(1650.0, 520.0), 300.0, 40.0),
+                    StaticObject::new(Vector2::new(2000.0, -300.0), 400.0, 1000.0),
+                ];
+
+                self.volumes = vec![
+                    // blows the player across the first gap
+                    Volume::new(
+                        Vector2::new(475.0, 100.0),
+                        350.0,
+                        400.0,
+                        VolumeEffect::Wind(Vector2::new(0.06, 0.0)),
+                    ),
+                    Volume::new(
+                        Vector2::new(1100.0, 200.0),
+                        300.0,
+                        400.0,
+                        VolumeEffect::GravityScale(0.35),
+                    ),
+                    // lets the player walk along the ceiling
+                    Volume::new(
+                        Vector2::new(1650.0, 300.0),
+                        300.0,
+                        400.0,
+                        VolumeEffect::InvertGravity,
+                    ),
+                ];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(2100.0, 260.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -200.0;
+            }
+
+            14 => {
+                self.name = "The Pool";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(100.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(600.0, -700.0), 600.0, 1000.0),
+                    StaticObject::new(Vector2::new(1100.0, -450.0), 400.0, 1000.0),
+                ];
+
+                // the player has to swim up out of the pool to reach the goal
+                self.volumes = vec![Volume::new(
+                    Vector2::new(600.0, -100.0),
+                    600.0,
+                    200.0,
+                    VolumeEffect::Water,
+                )];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(1200.0, 100.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -300.0;
+            }
+
+            15 => {
+                self.name = "Ladders and Vines";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(250.0, -500.0), 700.0, 1000.0),
+                    StaticObject::new(Vector2::new(500.0, 200.0), 200.0, 400.0),
+                    StaticObject::new(Vector2::new(1100.0, 350.0), 200.0, 100.0),
+                ];
+
+                self.volumes = vec![
+                    // a ladder up the side of the wall
+                    Volume::new(
+                        Vector2::new(370.0, 220.0),
+                        60.0,
+                        440.0,
+                        VolumeEffect::Climbable,
+                    ),
+                    // a vine hanging over the pit
+                    Volume::new(
+                        Vector2::new(800.0, 325.0),
+                        40.0,
+                        350.0,
+                        VolumeEffect::Climbable,
+                    ),
+                ];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(1150.0, 450.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -200.0;
+            }
+
+            16 => {
+                self.name = "Swinging";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(100.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(1700.0, -500.0), 400.0, 1000.0),
+                ];
+
+                self.grapple_anchors = vec![
+                    Circle::new(&Vector2::new(500.0, 250.0), 10.0, GRAPPLE_ANCHOR_COLOR),
+                    Circle::new(&Vector2::new(900.0, 250.0), 10.0, GRAPPLE_ANCHOR_COLOR),
+                    Circle::new(&Vector2::new(1300.0, 250.0), 10.0, GRAPPLE_ANCHOR_COLOR),
+                ];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(1750.0, 60.0), 20.0, GOAL_COLOR);
+
+                // low enough that swinging under the anchors doesn't kill the player
+                self.lowest_point = -400.0;
+            }
+
+            17 => {
+                self.name = "Crates";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(400.0, -500.0), 1200.0, 1000.0),
+                    StaticObject::new(Vector2::new(900.0, 65.0), 200.0, 130.0),
+                ];
+
+                // the ledge is too high to jump onto without standing on a crate
+                self.crates = vec![
+                    Crate::new(Vector2::new(400.0, 20.0), 40.0),
+                    Crate::new(Vector2::new(400.0, 60.0), 40.0),
+                ];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(950.0, 200.0), 20.0, GOAL_COLOR);
+
+                self.gems = vec![Gem::new(Vector2::new(650.0, 40.0))];
+
+                self.lowest_point = -200.0;
+            }
+
+            18 => {
+                self.name = "Taking Hits";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(500.0, -500.0), 1400.0, 1000.0),
+                    StaticObject::new(Vector2::new(1600.0, -500.0), 400.0, 1000.0),
+                ];
+
+                // too many hazards to dodge them all, but the player can take a few hits
+                self.hazards = fireball_bar(Vector2::new(400.0, 20.0), 4, 20.0, 8.0, 0.025, 0.0);
+                self.hazards.extend(fireball_bar(
+                    Vector2::new(800.0, 20.0),
+                    4,
+                    20.0,
+                    8.0,
+                    -0.025,
+                    PI,
+                ));
+                self.hazards.push(
+                    Hazard::new(&Vector2::new(1100.0, 15.0), 15.0, HAZARD_COLOR).with_damage(2),
+                );
+
+                self.checkpoints = vec![Checkpoint::new(
+                    Circle::new(&Vector2::new(1600.0, 20.0), 10.0, CHECKPOINT_COLOR),
+                    Vector2::new(1600.0, 20.0),
+                )];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(1750.0, 100.0), 20.0, GOAL_COLOR);
+
+                // out over the gap, past the big hazard
+                self.gems = vec![Gem::new(Vector2::new(1300.0, 60.0))];
+
+                self.lowest_point = -200.0;
+                self.max_health = Some(3);
+            }
+
+            19 => {
+                self.name = "Under Fire";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(600.0, -500.0), 1600.0, 1000.0),
+                    StaticObject::new(Vector2::new(700.0, 40.0), 40.0, 80.0),
+                    StaticObject::new(Vector2::new(1500.0, -500.0), 400.0, 1000.0),
+                ];
+
+                self.turrets = vec![
+                    // fires along the floor, so the player has to time their jumps
+                    Turret::new(
+                        &Vector2::new(1350.0, 15.0),
+                        15.0,
+                        TURRET_COLOR,
+                        TurretAim::Fixed(Vector2::new(-1.0, 0.0)),
+                        120.0,
+                        3.0,
+                    ),
+                    // only fires once the player is out from behind the wall
+                    Turret::new(
+                        &Vector2::new(1100.0, 60.0),
+                        15.0,
+                        TURRET_COLOR,
+                        TurretAim::AtPlayer { range: 600.0 },
+                        90.0,
+                        4.0,
+                    ),
+                ];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(1600.0, 100.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -200.0;
+                self.max_health = Some(3);
+            }
+
+            20 => {
+                self.name = "Bounce House";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(100.0, -500.0), 400.0, 1000.0),
+                    StaticObject::new(Vector2::new(1300.0, -500.0), 400.0, 1000.0),
+                ];
+
+                self.round_objects = vec![
+                    // round tops to hop across, which are hard to stand on near their edges
+                    RoundObject::new(&Vector2::new(450.0, -20.0), 60.0, ROUND_OBJECT_COLOR),
+                    RoundObject::new(&Vector2::new(650.0, 0.0), 50.0, ROUND_OBJECT_COLOR),
+                    // a bumper that launches the player over the last gap
+                    RoundObject::new(&Vector2::new(900.0, -60.0), 30.0, BUMPER_COLOR)
+                        .with_bounce(6.0),
+                ];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(1400.0, 100.0), 20.0, GOAL_COLOR);
+
+                self.lowest_point = -200.0;
+            }
+
+            21 => {
+                self.name = "Downhill";
+
+                self.static_objects = vec![
+                    StaticObject::new(Vector2::new(300.0, -500.0), 800.0, 1000.0),
+                    StaticObject::new(Vector2::new(1000.0, -300.0), 600.0, 1000.0),
+                    StaticObject::new(Vector2::new(1900.0, -500.0), 400.0, 1000.0),
+                ];
+
+                self.slopes = vec![
+                    // a ramp up onto the raised ground
+                    Slope::new(Vector2::new(500.0, 0.0), Vector2::new(700.0, 200.0), 0.0),
+                    // a long hill to run down
+                    Slope::new(
+                        Vector2::new(1300.0, 200.0),
+                        Vector2::new(1700.0, 0.0),
+                        -200.0,
+                    ),
+                ];
+
+                self.player_respawn = RigidBody {
+                    center: Vector2::new(0.0, 0.0),
+                    width: PLAYER_WIDTH,
+                    height: PLAYER_HEIGHT,
+
+                    velocity: Vector2::new(0.0, 0.0),
+                };
+
+                self.goal = Circle::new(&Vector2::new(1950.0, 100.0), 20.0, GOAL_COLOR);
+
+                self.gems = vec![Gem::new(Vector2::new(1000.0, 260.0))];
+
+                self.lowest_point = -200.0;
+            }
+
             _ => panic!("Map.load_map given improper level number"),
         }
 
         // set the starting player to the default player respawn
         self.player = self.player_respawn;
+        self.restore_health();
 
-        // put a moving platform end indicator
-        // at the end of all moving objects
+        // the level's start acts as the first checkpoint
+        self.save_checkpoint_state();
+
+        // put a moving platform end indicator at the ends
+        // of (or the pivot of) all moving objects
         self.moving_objects.iter().for_each(|object| {
-            self.moving_object_indicators.push(Circle::new(
-                &object.start_pos(),
-                MOVING_PLATFORM_INDICATOR_RADIUS,
-                MOVING_PLATFORM_INDICATOR_COLOR,
-            ));
-            self.moving_object_indicators.push(Circle::new(
-                &object.end_pos(),
-                MOVING_PLATFORM_INDICATOR_RADIUS,
-                MOVING_PLATFORM_INDICATOR_COLOR,
-            ));
+            for point in object.path_points() {
+                self.moving_object_indicators.push(Circle::new(
+                    &point,
+                    MOVING_PLATFORM_INDICATOR_RADIUS,
+                    MOVING_PLATFORM_INDICATOR_COLOR,
+                ));
+            }
         })
     }
 }
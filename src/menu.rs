@@ -0,0 +1,394 @@
+use minifb::Window;
+
+use super::{
+    camera::Rgb,
+    constants::{
+        DOWN_KEYS, GEM_COLOR, GEM_MISSING_COLOR, HUD_ICON_SIZE, LEFT_KEYS, LEVEL_COMPLETED_COLOR,
+        LEVEL_COUNT, LEVEL_LOCKED_COLOR, LEVEL_NUMBER_SCALE, LEVEL_SELECT_COLUMNS, LEVEL_TILE_SIZE,
+        LEVEL_TILE_SPACING, MENU_BACKGROUND_COLOR, MENU_BACK_KEYS, MENU_HEADING_SCALE,
+        MENU_ITEM_COLOR, MENU_ITEM_HEIGHT, MENU_ITEM_SPACING, MENU_ITEM_WIDTH,
+        MENU_SELECTED_BORDER, MENU_SELECTED_COLOR, MENU_SELECT_KEYS, MENU_TEXT_COLOR,
+        MENU_TEXT_SCALE, MENU_TOGGLE_OFF_COLOR, MENU_TOGGLE_ON_COLOR, MENU_TOGGLE_SIZE,
+        PAUSE_DIM_AMOUNT, RIGHT_KEYS, UP_KEYS, WINDOW_HEIGHT, WINDOW_WIDTH,
+    },
+    font::{render_centered_text, render_text, text_height},
+    game_player::any_key_pressed,
+    hud::fill_rect,
+    map::Map,
+    save::{SaveData, Settings},
+};
+
+/// something the player can pick in a menu
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    // the title menu
+    Play,
+    Continue,
+    LevelSelect,
+    Settings,
+    Quit,
+
+    // the pause menu
+    Resume,
+    RestartLevel,
+    RestartFromCheckpoint,
+    QuitToMenu,
+
+    // the settings menu
+    TimeTrials,
+    RaceGhosts,
+}
+
+impl MenuItem {
+    /// returns the text shown on the item
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::Play => "Play",
+            MenuItem::Continue => "Continue",
+            MenuItem::LevelSelect => "Level Select",
+            MenuItem::Settings => "Settings",
+            MenuItem::Quit => "Quit",
+            MenuItem::Resume => "Resume",
+            MenuItem::RestartLevel => "Restart Level",
+            MenuItem::RestartFromCheckpoint => "Restart from Checkpoint",
+            MenuItem::QuitToMenu => "Quit to Menu",
+            MenuItem::TimeTrials => "Time Trials",
+            MenuItem::RaceGhosts => "Race Ghosts",
+        }
+    }
+}
+
+/// which of a menu's options is selected, which is moved with the movement keys
+struct Menu {
+    selected: usize,
+    count: usize,
+    /// how many options are in each row (1 for a list)
+    columns: usize,
+}
+
+impl Menu {
+    fn new(count: usize, columns: usize, selected: usize) -> Menu {
+        Menu {
+            selected,
+            count,
+            columns,
+        }
+    }
+
+    // moves the selection if a movement key was pressed, staying on the menu
+    fn navigate(&mut self, window: &Window) {
+        let mut selected = self.selected as isize;
+        let columns = self.columns as isize;
+
+        if any_key_pressed(window, &UP_KEYS) {
+            selected -= columns;
+        }
+        if any_key_pressed(window, &DOWN_KEYS) {
+            selected += columns;
+        }
+        if columns > 1 && any_key_pressed(window, &LEFT_KEYS) {
+            selected -= 1;
+        }
+        if columns > 1 && any_key_pressed(window, &RIGHT_KEYS) {
+            selected += 1;
+        }
+
+        if (0..self.count as isize).contains(&selected) {
+            self.selected = selected as usize;
+        }
+    }
+}
+
+// shows a menu (drawn by the given function) until the player picks an option, returning
+// which one, or backs out of the menu or closes the window, returning none
+fn run_menu(
+    window: &mut Window,
+    menu: &mut Menu,
+    draw: impl Fn(&Menu, &mut [u32]),
+) -> Option<usize> {
+    let mut buffer: Vec<u32> = vec![0; WINDOW_WIDTH * WINDOW_HEIGHT];
+
+    while window.is_open() {
+        draw(menu, &mut buffer);
+
+        // keys are read after the window updates so the
+        // key that opened the menu doesn't count in it
+        window
+            .update_with_buffer(&buffer, WINDOW_WIDTH, WINDOW_HEIGHT)
+            .unwrap_or_else(|error| {
+                panic!("Error updating window: {}", error);
+            });
+
+        let picked = if any_key_pressed(window, &MENU_BACK_KEYS) {
+            None
+        } else if any_key_pressed(window, &MENU_SELECT_KEYS) {
+            Some(menu.selected)
+        } else {
+            menu.navigate(window);
+            continue;
+        };
+
+        // same for whatever comes after the menu
+        window.update();
+        return picked;
+    }
+
+    None
+}
+
+// draws a menu's heading centered near the top of the screen
+fn render_heading(heading: &str, buffer: &mut [u32]) {
+    render_centered_text(
+        heading,
+        WINDOW_WIDTH / 2,
+        WINDOW_HEIGHT / 10,
+        MENU_HEADING_SCALE,
+        MENU_TEXT_COLOR,
+        buffer,
+    );
+}
+
+// draws a column of items in the middle of the screen, with
+// a toggle on the right of the items that have one
+fn render_items(items: &[MenuItem], toggles: &[bool], selected: usize, buffer: &mut [u32]) {
+    let total_height = items.len() * (MENU_ITEM_HEIGHT + MENU_ITEM_SPACING) - MENU_ITEM_SPACING;
+    let left = (WINDOW_WIDTH - MENU_ITEM_WIDTH) / 2;
+    let text_top = (MENU_ITEM_HEIGHT - text_height(MENU_TEXT_SCALE)) / 2;
+
+    for (index, item) in items.iter().enumerate() {
+        let top =
+            (WINDOW_HEIGHT - total_height) / 2 + index * (MENU_ITEM_HEIGHT + MENU_ITEM_SPACING);
+        let color = match index == selected {
+            true => MENU_SELECTED_COLOR,
+            false => MENU_ITEM_COLOR,
+        };
+        fill_rect(
+            buffer,
+            left,
+            top,
+            MENU_ITEM_WIDTH,
+            MENU_ITEM_HEIGHT,
+            color.to_u32(),
+        );
+
+        match toggles.get(index) {
+            // settings have their name on the left and if they're on on the right
+            Some(on) => {
+                let margin = (MENU_ITEM_HEIGHT - MENU_TOGGLE_SIZE) / 2;
+                let color = match on {
+                    true => MENU_TOGGLE_ON_COLOR,
+                    false => MENU_TOGGLE_OFF_COLOR,
+                };
+
+                render_text(
+                    item.label(),
+                    left + text_top,
+                    top + text_top,
+                    MENU_TEXT_SCALE,
+                    MENU_TEXT_COLOR,
+                    buffer,
+                );
+                fill_rect(
+                    buffer,
+                    left + MENU_ITEM_WIDTH - margin - MENU_TOGGLE_SIZE,
+                    top + margin,
+                    MENU_TOGGLE_SIZE,
+                    MENU_TOGGLE_SIZE,
+                    color.to_u32(),
+                );
+            }
+            None => render_centered_text(
+                item.label(),
+                WINDOW_WIDTH / 2,
+                top + text_top,
+                MENU_TEXT_SCALE,
+                MENU_TEXT_COLOR,
+                buffer,
+            ),
+        }
+    }
+}
+
+/// shows the title menu, returning what the player picked,
+/// or none if they backed out of it or closed the window
+pub fn title_menu(window: &mut Window, save: &SaveData) -> Option<MenuItem> {
+    let items = [
+        MenuItem::Play,
+        MenuItem::Continue,
+        MenuItem::LevelSelect,
+        MenuItem::Settings,
+        MenuItem::Quit,
+    ];
+
+    // start on continue if the player has gotten anywhere
+    let mut menu = Menu::new(items.len(), 1, (save.unlocked_level > 1) as usize);
+
+    let picked = run_menu(window, &mut menu, |menu, buffer| {
+        buffer.fill(MENU_BACKGROUND_COLOR.to_u32());
+        render_heading("Platformer", buffer);
+        render_items(&items, &[], menu.selected, buffer);
+    });
+
+    picked.map(|index| items[index])
+}
+
+/// shows the pause menu over the last frame of the game, returning what the player
+/// picked (backing out resumes the game), or none if they closed the window
+pub fn pause_menu(window: &mut Window, frame: &[u32]) -> Option<MenuItem> {
+    let items = [
+        MenuItem::Resume,
+        MenuItem::RestartLevel,
+        MenuItem::RestartFromCheckpoint,
+        MenuItem::QuitToMenu,
+    ];
+    let mut menu = Menu::new(items.len(), 1, 0);
+
+    // darken the game so the menu stands out
+    let dimmed: Vec<u32> = frame
+        .iter()
+        .map(|pixel| {
+            Rgb::from_u32(*pixel)
+                .blend(PAUSE_DIM_AMOUNT, MENU_BACKGROUND_COLOR)
+                .to_u32()
+        })
+        .collect();
+
+    let picked = run_menu(window, &mut menu, |menu, buffer| {
+        buffer.copy_from_slice(&dimmed);
+        render_heading("Paused", buffer);
+        render_items(&items, &[], menu.selected, buffer);
+    });
+
+    match picked {
+        Some(index) => Some(items[index]),
+        None => window.is_open().then_some(MenuItem::Resume),
+    }
+}
+
+/// shows the settings menu, where picking a setting turns it on
+/// or off, until the player backs out of it or closes the window
+pub fn settings_menu(window: &mut Window, settings: &mut Settings) {
+    let items = [MenuItem::TimeTrials, MenuItem::RaceGhosts];
+    let mut menu = Menu::new(items.len(), 1, 0);
+
+    loop {
+        let toggles = [settings.time_trials, settings.race_ghosts];
+
+        let picked = run_menu(window, &mut menu, |menu, buffer| {
+            buffer.fill(MENU_BACKGROUND_COLOR.to_u32());
+            render_heading("Settings", buffer);
+            render_items(&items, &toggles, menu.selected, buffer);
+        });
+
+        match picked.map(|index| items[index]) {
+            Some(MenuItem::TimeTrials) => settings.time_trials = !settings.time_trials,
+            Some(MenuItem::RaceGhosts) => settings.race_ghosts = !settings.race_ghosts,
+            _ => return,
+        }
+    }
+}
+
+/**
+  shows a grid of every level, returning the one the player picked,
+  or none if they backed out of it or closed the window
+* locked levels can't be picked
+* finished levels are colored differently, and every level
+  shows which of its gems the player has finished it with
+*/
+pub fn level_select(window: &mut Window, save: &SaveData) -> Option<u32> {
+    // how many gems each level has
+    let gem_counts: Vec<usize> = (1..=LEVEL_COUNT)
+        .map(|level| {
+            let mut map = Map::new();
+            map.load_map(level);
+            map.gems.len()
+        })
+        .collect();
+
+    let columns = LEVEL_SELECT_COLUMNS;
+    let rows = (LEVEL_COUNT as usize).div_ceil(columns);
+    let grid_left =
+        (WINDOW_WIDTH - columns * (LEVEL_TILE_SIZE + LEVEL_TILE_SPACING) + LEVEL_TILE_SPACING) / 2;
+    let grid_top =
+        (WINDOW_HEIGHT - rows * (LEVEL_TILE_SIZE + LEVEL_TILE_SPACING) + LEVEL_TILE_SPACING) / 2;
+
+    let mut menu = Menu::new(
+        LEVEL_COUNT as usize,
+        columns,
+        save.unlocked_level as usize - 1,
+    );
+
+    loop {
+        let picked = run_menu(window, &mut menu, |menu, buffer| {
+            buffer.fill(MENU_BACKGROUND_COLOR.to_u32());
+            render_heading("Level Select", buffer);
+
+            for (index, gem_count) in gem_counts.iter().enumerate() {
+                let level = index as u32 + 1;
+                let progress = save.progress(level);
+                let left = grid_left + index % columns * (LEVEL_TILE_SIZE + LEVEL_TILE_SPACING);
+                let top = grid_top + index / columns * (LEVEL_TILE_SIZE + LEVEL_TILE_SPACING);
+
+                if index == menu.selected {
+                    fill_rect(
+                        buffer,
+                        left - MENU_SELECTED_BORDER,
+                        top - MENU_SELECTED_BORDER,
+                        LEVEL_TILE_SIZE + MENU_SELECTED_BORDER * 2,
+                        LEVEL_TILE_SIZE + MENU_SELECTED_BORDER * 2,
+                        MENU_SELECTED_COLOR.to_u32(),
+                    );
+                }
+
+                let (color, number_color) = match level <= save.unlocked_level {
+                    false => (LEVEL_LOCKED_COLOR, MENU_ITEM_COLOR),
+                    true if progress.completed => (LEVEL_COMPLETED_COLOR, MENU_TEXT_COLOR),
+                    true => (MENU_ITEM_COLOR, MENU_TEXT_COLOR),
+                };
+                fill_rect(
+                    buffer,
+                    left,
+                    top,
+                    LEVEL_TILE_SIZE,
+                    LEVEL_TILE_SIZE,
+                    color.to_u32(),
+                );
+
+                render_centered_text(
+                    &level.to_string(),
+                    left + LEVEL_TILE_SIZE / 2,
+                    top + (LEVEL_TILE_SIZE - text_height(LEVEL_NUMBER_SCALE)) / 2,
+                    LEVEL_NUMBER_SCALE,
+                    number_color,
+                    buffer,
+                );
+
+                // a row of gems along the bottom of the tile
+                let gem_size = HUD_ICON_SIZE / 2;
+                let gems_left = left + (LEVEL_TILE_SIZE - gem_count * gem_size * 2 + gem_size) / 2;
+                for gem in 0..*gem_count {
+                    let color = match progress.gems.get(gem) {
+                        Some(true) => GEM_COLOR,
+                        _ => GEM_MISSING_COLOR,
+                    };
+                    fill_rect(
+                        buffer,
+                        gems_left + gem * gem_size * 2,
+                        top + LEVEL_TILE_SIZE - gem_size * 2,
+                        gem_size,
+                        gem_size,
+                        color.to_u32(),
+                    );
+                }
+            }
+        });
+
+        match picked {
+            Some(index) if index < save.unlocked_level as usize => {
+                return Some(index as u32 + 1);
+            }
+            Some(_) => (),
+            None => return None,
+        }
+    }
+}
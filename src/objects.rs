@@ -1,10 +1,17 @@
 use std::{
+    f64::consts::PI,
     sync::{Arc, Mutex},
     thread::{self, JoinHandle},
     vec,
 };
 
-use crate::camera::Rgb;
+use crate::{
+    camera::Rgb,
+    constants::{
+        CORNER_CORRECTION_TOLERANCE, DOOR_OPEN_TIME, HAZARD_DAMAGE, LEDGE_NUDGE_TOLERANCE,
+        PROJECTILE_COLOR, PROJECTILE_RADIUS, ROUND_GROUND_NORMAL_Y,
+    },
+};
 
 // basic vector2 struct
 #[derive(Clone, Copy)]
@@ -42,6 +49,29 @@ impl Vector2 {
         )
     }
 
+    // returns vector1 minus vector2
+    pub fn subtract(vector1: &Vector2, vector2: &Vector2) -> Vector2 {
+        Vector2::new(vector1.x - vector2.x, vector1.y - vector2.y)
+    }
+
+    // returns the dot product of the two vectors
+    pub fn dot(vector1: &Vector2, vector2: &Vector2) -> f64 {
+        vector1.x * vector2.x + vector1.y * vector2.y
+    }
+
+    // returns the length of the vector
+    pub fn magnitude(vector: &Vector2) -> f64 {
+        Vector2::dot(vector, vector).sqrt()
+    }
+
+    // rotates the vector counterclockwise by the angle (in radians)
+    pub fn rotate(vector: &Vector2, angle: f64) -> Vector2 {
+        Vector2::new(
+            vector.x * angle.cos() - vector.y * angle.sin(),
+            vector.x * angle.sin() + vector.y * angle.cos(),
+        )
+    }
+
     // adds self to other
     pub fn add_to(&self, other: &mut Vector2) {
         other.set(&Vector2::add(self, other));
@@ -204,6 +234,23 @@ pub enum CollisionTypes {
     Right,
 }
 
+impl CollisionTypes {
+    // returns how far past this side of an object a rigidbody is pushed out,
+    // leaving a gap against ceilings to stop physics bugs but none against
+    // the ground (the side opposite gravity) so contact with it doesn't flicker
+    fn push_out_gap(self, gravity_direction: f64) -> f64 {
+        let ceiling = match gravity_direction > 0.0 {
+            true => CollisionTypes::Bottom,
+            false => CollisionTypes::Top,
+        };
+
+        match self == ceiling {
+            true => 1.0,
+            false => 0.0,
+        }
+    }
+}
+
 /// struct to represent an object with physics
 /// * movement must be handled manually
 /// * collision functions are provided
@@ -232,6 +279,72 @@ impl RigidBody {
         movement.add_to(&mut self.center);
     }
 
+    /// returns a copy of the rigidbody with a new height, keeping the side facing
+    /// down in place (or the side facing up if the direction is negative)
+    pub fn with_height(&self, height: f64, direction: f64) -> RigidBody {
+        RigidBody {
+            center: Vector2::new(
+                self.center.x,
+                self.center.y + direction * (height - self.height) / 2.0,
+            ),
+            height,
+            ..*self
+        }
+    }
+
+    /// moves the rigidbody with its velocity and the given acceleration
+    /// over the given time and updates its velocity
+    pub fn integrate(&mut self, acceleration: &Vector2, time: f64) {
+        // we integrate the movement to make the physics
+        // continuous and therefore framerate-independent
+        let movement_vector = Vector2::add(
+            // accel * t^2 / 2
+            &Vector2::multiply(acceleration, time * time / 2.0),
+            // vel * t
+            &Vector2::multiply(&self.velocity, time),
+            // c is already stored in the rigidbody's position
+            // and will be included when we add this movement
+            // vector to the rigidbody's current position
+        );
+
+        // apply the movement vector we calculated (adds c)
+        self.move_by(&movement_vector);
+
+        // update velocity (no integrating is needed as accel * t is exactly the growth in velocity)
+        Vector2::multiply(acceleration, time).add_to(&mut self.velocity);
+    }
+
+    /// if self is overlapping other from the side, moves
+    /// other out of the way and returns true
+    pub fn push_sideways(&self, other: &mut RigidBody) -> bool {
+        if !self.collides_with(other) {
+            return false;
+        }
+
+        let self_bounds = self.bounds();
+        let other_bounds = other.bounds();
+
+        // how far other would have to move to get out of self in each direction
+        let push_right = self_bounds.right - other_bounds.left;
+        let push_left = other_bounds.right - self_bounds.left;
+        let push_vertical = f64::min(
+            self_bounds.top - other_bounds.bottom,
+            other_bounds.top - self_bounds.bottom,
+        );
+
+        // self is on top of or under other, not beside it
+        if push_vertical <= f64::min(push_right, push_left) {
+            return false;
+        }
+
+        match push_right < push_left {
+            true => other.center.x += push_right,
+            false => other.center.x -= push_left,
+        }
+
+        true
+    }
+
     /**
     handles the collisions with an array of rect objects,
     puts the collision type into active_collision,
@@ -242,8 +355,9 @@ impl RigidBody {
         &mut self,
         objects: &[T],
         active_collisions: &mut Vec<CollisionTypes>,
+        gravity_direction: f64,
     ) -> Option<usize> {
-        let mut handles: Vec<JoinHandle<CollisionTypes>> = Vec::new();
+        let mut handles: Vec<JoinHandle<Option<CollisionTypes>>> = Vec::new();
 
         let platform_on: Arc<Mutex<Option<usize>>> = Arc::new(Mutex::new(None));
         let self_tracker = Arc::new(Mutex::new(*self));
@@ -254,6 +368,7 @@ impl RigidBody {
             }
 
             let self_bounds = self.bounds();
+            let self_velocity = self.velocity;
             let obj_bounds = object.bounds();
 
             let self_ptr = Arc::clone(&self_tracker);
@@ -279,13 +394,46 @@ impl RigidBody {
                     .unwrap()
                     .0;
 
+                // the sides of the object the rigidbody would hit its head on
+                // and stand on, which swap when gravity is inverted
+                let (ceiling_index, ground_index) = match gravity_direction > 0.0 {
+                    true => (2, 3),
+                    false => (3, 2),
+                };
+                let rising = self_velocity.y * gravity_direction > 0.0;
+
+                // if the rigidbody jumped into the corner of an object, nudge it
+                // around the corner instead of stopping it
+                let corner_correction = min_index == ceiling_index
+                    && rising
+                    && f64::min(left_depth, right_depth) <= CORNER_CORRECTION_TOLERANCE;
+
+                // if the rigidbody barely missed the side of an object it stands on, nudge it onto it
+                let ledge_nudge =
+                    min_index <= 1 && !rising && depths[ground_index] <= LEDGE_NUDGE_TOLERANCE;
+
+                let min_index = match (corner_correction, ledge_nudge) {
+                    (true, _) if left_depth < right_depth => 0,
+                    (true, _) => 1,
+                    (false, true) => ground_index,
+                    (false, false) => min_index,
+                };
+
                 // move the player outside of the platform
                 let mut guard = self_ptr.lock().unwrap();
                 match min_index {
                     0 => guard.center.x = obj_bounds.left - (guard.width / 2.0),
                     1 => guard.center.x = obj_bounds.right + (guard.width / 2.0),
-                    2 => guard.center.y = obj_bounds.bottom - (guard.height / 2.0) - 1.0, // -1.0 stops physics bugs
-                    3 => guard.center.y = obj_bounds.top + (guard.height / 2.0),
+                    2 => {
+                        guard.center.y = obj_bounds.bottom
+                            - (guard.height / 2.0)
+                            - CollisionTypes::Bottom.push_out_gap(gravity_direction)
+                    }
+                    3 => {
+                        guard.center.y = obj_bounds.top
+                            + (guard.height / 2.0)
+                            + CollisionTypes::Top.push_out_gap(gravity_direction)
+                    }
 
                     _ => panic!("Error: closest to no side handling rigidbody collisions"),
                 }
@@ -301,18 +449,24 @@ impl RigidBody {
                     _ => panic!("Error: closest to no side when handling rigidbody collisions"),
                 };
 
-                // update the platform that the player is on if they're on top of one
-                if current_collision == CollisionTypes::Top {
+                // update the platform that the player is on if they're standing on one
+                if min_index == ground_index {
                     *platform_on_ptr.lock().unwrap() = Some(index);
                 }
 
-                current_collision
+                // corner corrections shouldn't stop the rigidbody, so they don't count
+                match corner_correction {
+                    true => None,
+                    false => Some(current_collision),
+                }
             }));
         }
 
         // we unwrap because collision handling shouldn't panic
         for handle in handles {
-            active_collisions.push(handle.join().unwrap());
+            if let Some(collision) = handle.join().unwrap() {
+                active_collisions.push(collision);
+            }
         }
 
         let final_self = *self_tracker.lock().unwrap();
@@ -321,6 +475,140 @@ impl RigidBody {
         let index = *platform_on.lock().unwrap();
         index
     }
+
+    /**
+    handles the collisions with an array of round objects,
+    pushing self out along the contact normal, puts the
+    collision type into active_collision, and returns the
+    index of the object the player was on, if any
+    */
+    pub fn handle_round_collisions(
+        &mut self,
+        objects: &[RoundObject],
+        active_collisions: &mut Vec<CollisionTypes>,
+    ) -> Option<usize> {
+        let mut platform_on: Option<usize> = None;
+
+        for (index, object) in objects.iter().enumerate() {
+            if !object.circle.intersects_rigidbody(self) {
+                continue;
+            }
+
+            let Some((normal, depth)) = object.contact(self) else {
+                continue;
+            };
+
+            // move self outside of the object
+            Vector2::multiply(&normal, depth).add_to(&mut self.center);
+
+            // bumpers throw self away from them (keeping movement along their
+            // surface) and don't count as collisions
+            let into_object = Vector2::dot(&self.velocity, &normal);
+            if let Some(speed) = object.bounce() {
+                Vector2::multiply(&normal, speed - into_object).add_to(&mut self.velocity);
+                continue;
+            }
+
+            // stop any movement into the object, keeping movement along its surface
+            if into_object < 0.0 {
+                Vector2::multiply(&normal, -into_object).add_to(&mut self.velocity);
+            }
+
+            // finds what kind of collision it was, counting
+            // anywhere near the top of the object as standing on it
+            let current_collision = if normal.y >= ROUND_GROUND_NORMAL_Y {
+                CollisionTypes::Top
+            } else if normal.y <= -ROUND_GROUND_NORMAL_Y {
+                CollisionTypes::Bottom
+            } else if normal.x < 0.0 {
+                CollisionTypes::Left
+            } else {
+                CollisionTypes::Right
+            };
+
+            if current_collision == CollisionTypes::Top {
+                platform_on = Some(index);
+            }
+
+            active_collisions.push(current_collision);
+        }
+
+        platform_on
+    }
+
+    /**
+    handles the collisions with an array of slopes, walking
+    self up their surfaces and stopping it at their ends,
+    puts the collision type into active_collision, and
+    returns the index of the slope the player was on, if any
+    */
+    pub fn handle_slope_collisions(
+        &mut self,
+        slopes: &[Slope],
+        active_collisions: &mut Vec<CollisionTypes>,
+        gravity_direction: f64,
+    ) -> Option<usize> {
+        let mut slope_on: Option<usize> = None;
+
+        for (index, slope) in slopes.iter().enumerate() {
+            let self_bounds = self.bounds();
+            let Some(surface) = slope.highest_surface(self_bounds.left, self_bounds.right) else {
+                continue;
+            };
+
+            if self_bounds.bottom >= surface || self_bounds.top <= slope.bottom() {
+                continue;
+            }
+
+            // determine how far self would have to move to get out each way
+            let top_depth = surface - self_bounds.bottom;
+            let left_depth = self_bounds.right - slope.start().x;
+            let right_depth = slope.end().x - self_bounds.left;
+            let bottom_depth = self_bounds.top - slope.bottom();
+
+            // walking onto the surface wins ties, so the low end of a ramp isn't a wall
+            let depths = [top_depth, left_depth, right_depth, bottom_depth];
+            let min_index = depths
+                .iter()
+                .enumerate()
+                .reduce(|acc, item| match acc.1 <= item.1 {
+                    true => acc,
+                    false => item,
+                })
+                .unwrap()
+                .0;
+
+            let current_collision = match min_index {
+                0 => {
+                    self.center.y = surface
+                        + self.height / 2.0
+                        + CollisionTypes::Top.push_out_gap(gravity_direction);
+                    slope_on = Some(index);
+                    CollisionTypes::Top
+                }
+                1 => {
+                    self.center.x = slope.start().x - self.width / 2.0;
+                    CollisionTypes::Left
+                }
+                2 => {
+                    self.center.x = slope.end().x + self.width / 2.0;
+                    CollisionTypes::Right
+                }
+                3 => {
+                    self.center.y = slope.bottom()
+                        - self.height / 2.0
+                        - CollisionTypes::Bottom.push_out_gap(gravity_direction);
+                    CollisionTypes::Bottom
+                }
+
+                _ => panic!("Error: closest to no side when handling slope collisions"),
+            };
+
+            active_collisions.push(current_collision);
+        }
+
+        slope_on
+    }
 }
 
 impl RectObject for RigidBody {
@@ -337,15 +625,67 @@ impl RectObject for RigidBody {
     }
 }
 
+//
+// Orbit code
+//
+
+/// a circular path around a pivot
+#[derive(Clone, Copy)]
+pub struct Orbit {
+    pub pivot: Vector2,
+    pub radius: f64,
+    /// radians per hundredth of a second (positive is counterclockwise)
+    pub angular_speed: f64,
+    /// the angle (in radians) at time 0
+    pub start_angle: f64,
+}
+
+impl Orbit {
+    pub fn new(pivot: Vector2, radius: f64, angular_speed: f64, start_angle: f64) -> Orbit {
+        Orbit {
+            pivot,
+            radius,
+            angular_speed,
+            start_angle,
+        }
+    }
+
+    /// returns where on the orbit something following it is at the given time
+    pub fn position(&self, time: f64) -> Vector2 {
+        let angle = self.start_angle + self.angular_speed * time;
+
+        Vector2::new(
+            self.pivot.x + self.radius * angle.cos(),
+            self.pivot.y + self.radius * angle.sin(),
+        )
+    }
+}
+
 //
 // MovingObject code
 //
 
+/// describes what makes a moving object move
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    /// always moves back and forth with the level clock
+    Always,
+    /// waits at its start until stood on, then travels to its end and stays there
+    OneShot,
+    /// travels toward its end while stood on and back to its start when vacated
+    WhileStoodOn,
+    /// moves back and forth only while a switch targeting it is on
+    Switched,
+}
+
 #[derive(Clone)]
-/// a RectObject that moves between two fixed points
+/// a RectObject that moves between two fixed points or around an orbit
+/// (orbiting objects stay upright so they can still be stood on)
 pub struct MovingObject {
     start_pos: Vector2,
     end_pos: Vector2,
+    /// if set, the object follows this instead of moving between start_pos and end_pos
+    orbit: Option<Orbit>,
 
     width: f64,
     height: f64,
@@ -353,21 +693,32 @@ pub struct MovingObject {
 
     /// describes how far the object has traveled (0-1 is going to end_pos, 1-2 is returning to start_pos)
     amount_traveled: f64,
+    /// how far along its path the object is when the level clock is at 0 (same units as amount_traveled)
+    phase_offset: f64,
     center: Vector2,
 
     /// the motion on the object's last update
     prev_move: Vector2,
+    /// how fast the object was moving on its last update (per hundredth of a second)
+    velocity: Vector2,
+
+    /// the id switches use to reference this object
+    id: Option<u32>,
+    activation: Activation,
+    /// if the object has been activated (unused for Activation::Always)
+    active: bool,
+    /// the level clock time of the object's last update
+    last_time: f64,
 }
 
 impl MovingObject {
-    /// returns the object's start position
-    pub fn start_pos(&self) -> Vector2 {
-        self.start_pos
-    }
-
-    /// returns the object's end position
-    pub fn end_pos(&self) -> Vector2 {
-        self.end_pos
+    /// returns the points that define the object's path
+    /// (its start and end positions, or the pivot of its orbit)
+    pub fn path_points(&self) -> Vec<Vector2> {
+        match self.orbit {
+            Some(orbit) => vec![orbit.pivot],
+            None => vec![self.start_pos, self.end_pos],
+        }
     }
 
     /// returns the object's previous move
@@ -375,6 +726,16 @@ impl MovingObject {
         self.prev_move
     }
 
+    /// returns how fast the object was moving on its last update
+    pub fn velocity(&self) -> Vector2 {
+        self.velocity
+    }
+
+    /// returns the object's id
+    pub fn id(&self) -> Option<u32> {
+        self.id
+    }
+
     /// creates a new leaving moving platform at start point
     pub fn new(
         start_pos: Vector2,
@@ -388,31 +749,114 @@ impl MovingObject {
         MovingObject {
             start_pos,
             end_pos,
+            orbit: None,
             width,
             height,
             move_time,
 
             amount_traveled: 0.0,
+            phase_offset: 0.0,
             center,
 
             prev_move: Vector2::new(0.0, 0.0),
+            velocity: Vector2::new(0.0, 0.0),
+
+            id: None,
+            activation: Activation::Always,
+            active: false,
+            last_time: 0.0,
         }
     }
 
+    /// creates a new moving platform following an orbit, which
+    /// goes half way around the orbit for every 1 of amount_traveled
+    pub fn new_orbit(orbit: Orbit, width: f64, height: f64) -> MovingObject {
+        let mut object = MovingObject::new(
+            orbit.position(0.0),
+            orbit.position(0.0),
+            width,
+            height,
+            PI / orbit.angular_speed.abs(),
+        );
+        object.orbit = Some(orbit);
+
+        object
+    }
+
+    /// staggers the object along its path by the given offset
+    /// (0-1 is going to end_pos, 1-2 is returning to start_pos)
+    pub fn with_phase_offset(mut self, phase_offset: f64) -> MovingObject {
+        self.phase_offset = phase_offset;
+        self.reset(0.0);
+
+        self
+    }
+
+    /// gives the object an id for switches to reference
+    pub fn with_id(mut self, id: u32) -> MovingObject {
+        self.id = Some(id);
+
+        self
+    }
+
+    /// sets what makes the object move
+    pub fn with_activation(mut self, activation: Activation) -> MovingObject {
+        self.activation = activation;
+
+        self
+    }
+
+    /// tells the object if the player is standing on it
+    pub fn set_stood_on(&mut self, stood_on: bool) {
+        match self.activation {
+            Activation::OneShot => self.active |= stood_on,
+            Activation::WhileStoodOn => self.active = stood_on,
+            Activation::Always | Activation::Switched => (),
+        }
+    }
+
+    /// tells the object if a switch targeting it is on
+    pub fn set_switched_on(&mut self, switched_on: bool) {
+        if self.activation == Activation::Switched {
+            self.active = switched_on;
+        }
+    }
+
+    /// puts the object back where it starts and deactivates it
+    pub fn reset(&mut self, time: f64) {
+        self.active = false;
+        self.amount_traveled = self.phase_offset;
+        self.last_time = time;
+
+        self.update(time);
+        self.prev_move = Vector2::new(0.0, 0.0);
+        self.velocity = Vector2::new(0.0, 0.0);
+    }
+
     /**
-     * moves the object across its path and stores the new position
+     * moves the object to where it should be at the given time
+     * on the level clock and stores the new position
      * will automatically change direction
-     * will panic on negative amount values
-     * returns the movement the object took
+     * stores the movement the object took since its last update
      */
-    pub fn update(&mut self, amount: f64) {
+    pub fn update(&mut self, time: f64) {
         let pre_center: Vector2 = Vector2::clone(&self.center);
 
-        // update the amount of path traveled
-        self.amount_traveled += amount / self.move_time;
-
-        // this prevents overflow of the amount traveled
-        self.amount_traveled %= 2.0;
+        // how much of the path the object could have traveled since its last update
+        let elapsed = time - self.last_time;
+        let path_delta = elapsed / self.move_time;
+        self.last_time = time;
+
+        // find the amount of path traveled (rem_euclid prevents
+        // overflow and keeps the amount positive)
+        self.amount_traveled = match (self.activation, self.active) {
+            (Activation::Always, _) => (time / self.move_time + self.phase_offset).rem_euclid(2.0),
+            (Activation::OneShot, true) => f64::min(self.amount_traveled + path_delta, 1.0),
+            (Activation::WhileStoodOn, true) => f64::min(self.amount_traveled + path_delta, 1.0),
+            (Activation::WhileStoodOn, false) => f64::max(self.amount_traveled - path_delta, 0.0),
+            (Activation::Switched, true) => (self.amount_traveled + path_delta).rem_euclid(2.0),
+            (Activation::OneShot | Activation::Switched, false) => self.amount_traveled,
+        };
 
         // configures the lerp amount
         let lerp_amount: f64 = match self.amount_traveled < 1.0 {
@@ -420,12 +864,24 @@ impl MovingObject {
             false => 2.0 - self.amount_traveled,
         };
 
-        // lerp between the two points to determine the center of the moving platform
-        self.center
-            .set(&Vector2::lerp(&self.start_pos, &self.end_pos, lerp_amount));
+        // follow the orbit, or lerp between the two points
+        // to determine the center of the moving platform
+        match self.orbit {
+            Some(orbit) => self
+                .center
+                .set(&orbit.position(self.amount_traveled * self.move_time)),
+            None => self
+                .center
+                .set(&Vector2::lerp(&self.start_pos, &self.end_pos, lerp_amount)),
+        }
 
         // return the moved amount by subtracting previous position from new position
-        self.prev_move = Vector2::add(&Vector2::multiply(&pre_center, -1.0), &self.center)
+        self.prev_move = Vector2::add(&Vector2::multiply(&pre_center, -1.0), &self.center);
+
+        // an update with no time passing doesn't change how fast the object is going
+        if elapsed > 0.0 {
+            self.velocity = Vector2::multiply(&self.prev_move, 1.0 / elapsed);
+        }
     }
 }
 
@@ -443,6 +899,242 @@ impl RectObject for MovingObject {
     }
 }
 
+//
+// Switch code
+//
+
+#[derive(Clone)]
+/// a floor switch that toggles the objects with its target ids
+/// each time the player or a crate steps on it
+/// * it stays on after being stepped off of, since most levels
+///   with switches have nothing to keep them weighed down
+pub struct Switch {
+    center: Vector2,
+    width: f64,
+    height: f64,
+
+    /// the ids of the objects this switch controls
+    targets: Vec<u32>,
+
+    /// if the switch is being stood on
+    pressed: bool,
+    /// if the switch is turning its targets on
+    pub on: bool,
+}
+
+impl Switch {
+    pub fn new(center: Vector2, width: f64, height: f64, targets: Vec<u32>) -> Switch {
+        Switch {
+            center,
+            width,
+            height,
+            targets,
+            pressed: false,
+            on: false,
+        }
+    }
+
+    /// returns if the switch controls the object with the given id
+    pub fn targets(&self, id: u32) -> bool {
+        self.targets.contains(&id)
+    }
+
+    /// tells the switch if it is being stood on,
+    /// toggling it when it is first stepped on
+    pub fn set_pressed(&mut self, pressed: bool) {
+        if pressed && !self.pressed {
+            self.on = !self.on;
+        }
+
+        self.pressed = pressed;
+    }
+
+    /// turns the switch back off
+    pub fn reset(&mut self) {
+        self.pressed = false;
+        self.on = false;
+    }
+}
+
+impl RectObject for Switch {
+    fn center(&self) -> Vector2 {
+        self.center
+    }
+
+    fn width(&self) -> f64 {
+        self.width
+    }
+
+    fn height(&self) -> f64 {
+        self.height
+    }
+}
+
+//
+// Door code
+//
+
+#[derive(Clone)]
+/// a solid object that opens when the player touches it holding its key,
+/// or while a switch targeting it is on
+pub struct Door {
+    center: Vector2,
+    width: f64,
+    height: f64,
+
+    /// the key that opens the door
+    key: Option<u32>,
+    /// the id switches use to reference this door
+    id: Option<u32>,
+
+    /// how far the door has opened (0 is closed, 1 is fully open)
+    open_amount: f64,
+    opening: bool,
+}
+
+impl Door {
+    /// creates a new closed door
+    pub fn new(center: Vector2, width: f64, height: f64) -> Door {
+        Door {
+            center,
+            width,
+            height,
+            key: None,
+            id: None,
+            open_amount: 0.0,
+            opening: false,
+        }
+    }
+
+    /// makes the door open for the given key
+    pub fn with_key(mut self, key: u32) -> Door {
+        self.key = Some(key);
+
+        self
+    }
+
+    /// gives the door an id for switches to reference
+    pub fn with_id(mut self, id: u32) -> Door {
+        self.id = Some(id);
+
+        self
+    }
+
+    /// returns the key that opens the door
+    pub fn key(&self) -> Option<u32> {
+        self.key
+    }
+
+    /// returns the door's id
+    pub fn id(&self) -> Option<u32> {
+        self.id
+    }
+
+    /// starts opening the door
+    pub fn open(&mut self) {
+        self.opening = true;
+    }
+
+    /// opens the door while a switch targeting it is on,
+    /// and starts closing it again once the switch turns off
+    pub fn set_switched_on(&mut self, switched_on: bool) {
+        self.opening = switched_on;
+    }
+
+    /// returns if the door has started opening
+    pub fn is_opening(&self) -> bool {
+        self.opening
+    }
+
+    /// returns if the door is fully open (and should no longer collide)
+    pub fn is_open(&self) -> bool {
+        self.open_amount >= 1.0
+    }
+
+    /// raises the door if it is opening, and lowers it otherwise
+    pub fn update(&mut self, frame_time: f64) {
+        let change = frame_time / DOOR_OPEN_TIME;
+
+        self.open_amount = match self.opening {
+            true => f64::min(self.open_amount + change, 1.0),
+            false => f64::max(self.open_amount - change, 0.0),
+        };
+    }
+}
+
+impl RectObject for Door {
+    // the door rises by shrinking with its top kept in place
+    fn center(&self) -> Vector2 {
+        Vector2::new(
+            self.center.x,
+            self.center.y + self.height * self.open_amount / 2.0,
+        )
+    }
+
+    fn width(&self) -> f64 {
+        self.width
+    }
+
+    fn height(&self) -> f64 {
+        self.height * (1.0 - self.open_amount)
+    }
+}
+
+//
+// Volume code
+//
+
+/// what a volume does to the player while they're inside it
+#[derive(Clone, Copy)]
+pub enum VolumeEffect {
+    /// pushes the player with a constant acceleration
+    Wind(Vector2),
+    /// multiplies the strength of gravity
+    GravityScale(f64),
+    /// makes gravity pull the player up instead of down
+    InvertGravity,
+    /// weakens gravity, slows the player down, and lets them swim
+    Water,
+    /// lets the player climb up and down (ladders and vines)
+    Climbable,
+}
+
+#[derive(Clone)]
+/// a rectangular region with no collisions that
+/// changes the player's physics while they're inside
+pub struct Volume {
+    center: Vector2,
+    width: f64,
+    height: f64,
+
+    pub effect: VolumeEffect,
+}
+
+impl Volume {
+    pub fn new(center: Vector2, width: f64, height: f64, effect: VolumeEffect) -> Volume {
+        Volume {
+            center,
+            width,
+            height,
+            effect,
+        }
+    }
+}
+
+impl RectObject for Volume {
+    fn center(&self) -> Vector2 {
+        self.center
+    }
+
+    fn width(&self) -> f64 {
+        self.width
+    }
+
+    fn height(&self) -> f64 {
+        self.height
+    }
+}
+
 //
 // StaticObject code
 //
@@ -484,6 +1176,7 @@ impl RectObject for StaticObject {
 //
 
 /// a circle with no collisions used to indicate different things
+#[derive(Clone)]
 pub struct Circle {
     center: Vector2,
     radius: f64,
@@ -499,6 +1192,10 @@ impl Circle {
         }
     }
 
+    pub fn center(&self) -> Vector2 {
+        self.center
+    }
+
     pub fn contains_point(&self, point: &Vector2) -> bool {
         let vector_from_center = Vector2::new(point.x - self.center.x, point.y - self.center.y);
         let distance_from_center_squared = vector_from_center.x * vector_from_center.x
@@ -534,3 +1231,369 @@ impl Circle {
         corner_distance_squared <= self.radius * self.radius
     }
 }
+
+//
+// Slope code
+//
+
+/// solid ground under a straight (but not necessarily flat)
+/// surface, filled in down to a flat bottom
+#[derive(Clone, Copy)]
+pub struct Slope {
+    start: Vector2,
+    end: Vector2,
+    bottom: f64,
+}
+
+impl Slope {
+    /// creates a new slope with a surface between the two points
+    /// * a point at the same height as the bottom makes a ramp
+    pub fn new(start: Vector2, end: Vector2, bottom: f64) -> Slope {
+        match start.x < end.x {
+            true => Slope { start, end, bottom },
+            false => Slope {
+                start: end,
+                end: start,
+                bottom,
+            },
+        }
+    }
+
+    /// the left end of the surface
+    pub fn start(&self) -> Vector2 {
+        self.start
+    }
+
+    /// the right end of the surface
+    pub fn end(&self) -> Vector2 {
+        self.end
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.bottom
+    }
+
+    /// returns the height of the surface at x (which should be on the slope)
+    pub fn surface_height(&self, x: f64) -> f64 {
+        let t = (x - self.start.x) / (self.end.x - self.start.x);
+        self.start.y + (self.end.y - self.start.y) * t.clamp(0.0, 1.0)
+    }
+
+    /// returns the highest the surface gets between left and
+    /// right, or none if the slope isn't between them at all
+    pub fn highest_surface(&self, left: f64, right: f64) -> Option<f64> {
+        if right <= self.start.x || self.end.x <= left {
+            return None;
+        }
+
+        // the surface is straight, so the highest point is at one of the ends
+        Some(f64::max(
+            self.surface_height(f64::max(left, self.start.x)),
+            self.surface_height(f64::min(right, self.end.x)),
+        ))
+    }
+
+    /// determines if a Vector2 lies within the slope
+    pub fn contains_point(&self, point: &Vector2) -> bool {
+        self.start.x <= point.x
+            && point.x <= self.end.x
+            && self.bottom <= point.y
+            && point.y <= self.surface_height(point.x)
+    }
+
+    /// returns if a body overlaps the slope
+    pub fn overlaps(&self, body: &RigidBody) -> bool {
+        let bounds = body.bounds();
+
+        self.highest_surface(bounds.left, bounds.right)
+            .is_some_and(|surface| bounds.bottom < surface && self.bottom < bounds.top)
+    }
+}
+
+//
+// RoundObject code
+//
+
+/// a solid circle, which can optionally bounce things away like a bumper
+#[derive(Clone)]
+pub struct RoundObject {
+    pub circle: Circle,
+    bounce: Option<f64>,
+}
+
+impl RoundObject {
+    pub fn new(center: &Vector2, radius: f64, color: Rgb) -> RoundObject {
+        RoundObject {
+            circle: Circle::new(center, radius, color),
+            bounce: None,
+        }
+    }
+
+    /// makes the object throw anything that touches it away at the given speed
+    pub fn with_bounce(mut self, speed: f64) -> RoundObject {
+        self.bounce = Some(speed);
+        self
+    }
+
+    pub fn bounce(&self) -> Option<f64> {
+        self.bounce
+    }
+
+    /// returns the direction to push a body to get it out of the
+    /// object and how far to push it, if they are overlapping
+    pub fn contact(&self, body: &RigidBody) -> Option<(Vector2, f64)> {
+        let center = self.circle.center;
+        let radius = self.circle.radius;
+        let bounds = body.bounds();
+
+        // find the point on the body closest to the center of the circle
+        let closest = Vector2::new(
+            center.x.clamp(bounds.left, bounds.right),
+            center.y.clamp(bounds.bottom, bounds.top),
+        );
+        let offset = Vector2::subtract(&closest, &center);
+        let distance = Vector2::magnitude(&offset);
+
+        if distance >= radius {
+            return None;
+        }
+
+        if distance > 0.0 {
+            return Some((
+                Vector2::multiply(&offset, 1.0 / distance),
+                radius - distance,
+            ));
+        }
+
+        // the circle's center is inside the body, so push it out the shortest way
+        [
+            (Vector2::new(-1.0, 0.0), bounds.right - (center.x - radius)),
+            (Vector2::new(1.0, 0.0), center.x + radius - bounds.left),
+            (Vector2::new(0.0, -1.0), bounds.top - (center.y - radius)),
+            (Vector2::new(0.0, 1.0), center.y + radius - bounds.bottom),
+        ]
+        .into_iter()
+        .reduce(|acc, item| match acc.1 < item.1 {
+            true => acc,
+            false => item,
+        })
+    }
+}
+
+//
+// LineSegment code
+//
+
+/// a straight line between two points
+#[derive(Clone, Copy)]
+pub struct LineSegment {
+    pub start: Vector2,
+    pub end: Vector2,
+}
+
+impl LineSegment {
+    pub fn new(start: Vector2, end: Vector2) -> LineSegment {
+        LineSegment { start, end }
+    }
+
+    /// returns the point on the segment closest to the given point
+    pub fn closest_point(&self, point: &Vector2) -> Vector2 {
+        let direction = Vector2::subtract(&self.end, &self.start);
+        let length_squared = Vector2::dot(&direction, &direction);
+
+        // a segment with no length is just a point
+        if length_squared == 0.0 {
+            return self.start;
+        }
+
+        // find how far along the segment the point is, keeping it on the segment
+        let t = Vector2::dot(&Vector2::subtract(point, &self.start), &direction) / length_squared;
+
+        Vector2::lerp(&self.start, &self.end, t.clamp(0.0, 1.0))
+    }
+
+    /// returns the distance from the segment to the given point
+    pub fn distance_to_point(&self, point: &Vector2) -> f64 {
+        Vector2::magnitude(&Vector2::subtract(point, &self.closest_point(point)))
+    }
+
+    /// returns if any part of the segment passes through the bounds
+    pub fn intersects_bounds(&self, bounds: &Bounds) -> bool {
+        let direction = Vector2::subtract(&self.end, &self.start);
+
+        // clip the segment against each pair of sides, keeping
+        // track of the part of the segment inside all of them
+        let mut t_min: f64 = 0.0;
+        let mut t_max: f64 = 1.0;
+
+        for (start, direction, low, high) in [
+            (self.start.x, direction.x, bounds.left, bounds.right),
+            (self.start.y, direction.y, bounds.bottom, bounds.top),
+        ] {
+            if direction == 0.0 {
+                // a segment parallel to these sides has to start between them
+                if start < low || high < start {
+                    return false;
+                }
+                continue;
+            }
+
+            let t1 = (low - start) / direction;
+            let t2 = (high - start) / direction;
+
+            t_min = f64::max(t_min, f64::min(t1, t2));
+            t_max = f64::min(t_max, f64::max(t1, t2));
+
+            if t_min > t_max {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+//
+// Hazard code
+//
+
+/// a circle that hurts the player, optionally following an orbit
+#[derive(Clone)]
+pub struct Hazard {
+    pub circle: Circle,
+    orbit: Option<Orbit>,
+    damage: u32,
+}
+
+impl Hazard {
+    /// creates a new hazard that stays in place
+    pub fn new(center: &Vector2, radius: f64, color: Rgb) -> Hazard {
+        Hazard {
+            circle: Circle::new(center, radius, color),
+            orbit: None,
+            damage: HAZARD_DAMAGE,
+        }
+    }
+
+    /// creates a new hazard that follows an orbit
+    pub fn new_orbit(orbit: Orbit, radius: f64, color: Rgb) -> Hazard {
+        Hazard {
+            circle: Circle::new(&orbit.position(0.0), radius, color),
+            orbit: Some(orbit),
+            damage: HAZARD_DAMAGE,
+        }
+    }
+
+    /// makes the hazard take away more (or less) health in levels with health
+    pub fn with_damage(mut self, damage: u32) -> Hazard {
+        self.damage = damage;
+        self
+    }
+
+    pub fn damage(&self) -> u32 {
+        self.damage
+    }
+
+    /// moves the hazard to where it should be at the given time on the level clock
+    pub fn update(&mut self, time: f64) {
+        if let Some(orbit) = self.orbit {
+            self.circle.center = orbit.position(time);
+        }
+    }
+}
+
+//
+// Turret code
+//
+
+/// where a turret points when it fires
+#[derive(Clone, Copy)]
+pub enum TurretAim {
+    /// fires in the same direction on a timer
+    Fixed(Vector2),
+    /// fires at the player whenever they're within range and can be seen
+    AtPlayer { range: f64 },
+}
+
+/// a circle that fires projectiles
+#[derive(Clone)]
+pub struct Turret {
+    pub circle: Circle,
+    pub aim: TurretAim,
+    fire_interval: f64,
+    projectile_speed: f64,
+    reload: f64,
+}
+
+impl Turret {
+    /// creates a new turret that fires every fire_interval hundredths of a second
+    pub fn new(
+        center: &Vector2,
+        radius: f64,
+        color: Rgb,
+        aim: TurretAim,
+        fire_interval: f64,
+        projectile_speed: f64,
+    ) -> Turret {
+        Turret {
+            circle: Circle::new(center, radius, color),
+            aim,
+            fire_interval,
+            projectile_speed,
+            reload: fire_interval,
+        }
+    }
+
+    /// makes the turret wait its full interval before firing again
+    pub fn reset(&mut self) {
+        self.reload = self.fire_interval;
+    }
+
+    /// counts down the turret's reload and fires a projectile in the
+    /// direction given if there is one and the turret is ready
+    pub fn update(&mut self, frame_time: f64, direction: Option<Vector2>) -> Option<Projectile> {
+        self.reload -= frame_time;
+
+        let direction = direction?;
+        if self.reload > 0.0 {
+            return None;
+        }
+
+        self.reload = self.fire_interval;
+
+        let magnitude = Vector2::magnitude(&direction);
+        if magnitude == 0.0 {
+            return None;
+        }
+
+        Some(Projectile {
+            circle: Circle::new(&self.circle.center, PROJECTILE_RADIUS, PROJECTILE_COLOR),
+            velocity: Vector2::multiply(&direction, self.projectile_speed / magnitude),
+            age: 0.0,
+        })
+    }
+}
+
+/// a circle fired by a turret that travels in a straight line
+#[derive(Clone)]
+pub struct Projectile {
+    pub circle: Circle,
+    velocity: Vector2,
+    age: f64,
+}
+
+impl Projectile {
+    /// moves the projectile along its path
+    pub fn update(&mut self, frame_time: f64) {
+        self.circle.center = Vector2::add(
+            &self.circle.center,
+            &Vector2::multiply(&self.velocity, frame_time),
+        );
+        self.age += frame_time;
+    }
+
+    /// returns how long the projectile has been flying (in hundredths of a second)
+    pub fn age(&self) -> f64 {
+        self.age
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    constants::{LEVEL_COUNT, SIMULATION_TICK_HUNDREDTH_SECONDS},
+    objects::{RigidBody, Vector2},
+    save::write_atomically,
+};
+
+/// converts a number of simulation ticks to hundredths of a second
+pub fn ticks_to_hundredths(ticks: u64) -> u64 {
+    (ticks as f64 * SIMULATION_TICK_HUNDREDTH_SECONDS).round() as u64
+}
+
+/// a time trial run, which may not have made it to the end
+#[derive(Clone)]
+pub struct Attempt {
+    /// how many ticks each level the run got through took
+    pub splits: Vec<u64>,
+    /// if the run finished every level
+    pub completed: bool,
+}
+
+/// the player's personal bests and history in time trials
+pub struct Records {
+    /// how many ticks each level took in the fastest full run
+    pub best_run: Option<Vec<u64>>,
+    /// the fastest each level has been finished (index 0 is level 1)
+    pub best_splits: Vec<Option<u64>>,
+    /// every time trial run, oldest first
+    pub attempts: Vec<Attempt>,
+}
+
+impl Records {
+    pub fn new() -> Records {
+        Records {
+            best_run: None,
+            best_splits: Vec::new(),
+            attempts: Vec::new(),
+        }
+    }
+
+    /// reads a record written by write_lines, returning if the line was one
+    pub fn read_line(&mut self, line: &str) -> bool {
+        let mut words = line.split_whitespace();
+        let kind = words.next();
+        let numbers: Option<Vec<u64>> = words.map(|word| word.parse().ok()).collect();
+
+        match (kind, numbers.as_deref()) {
+            (Some("split"), Some([level, ticks])) if (1..=LEVEL_COUNT as u64).contains(level) => {
+                self.record_split(*level as u32, *ticks);
+            }
+            (Some("run"), Some(splits)) if !splits.is_empty() => {
+                // a run with a different number of levels can't be compared with a
+                // full one (it's from before levels were added), so it's dropped
+                if splits.len() == LEVEL_COUNT as usize {
+                    self.record_run(splits);
+                }
+            }
+            (Some("attempt"), Some([completed, splits @ ..])) => {
+                self.attempts.push(Attempt {
+                    splits: splits.to_vec(),
+                    completed: *completed == 1,
+                });
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// writes the records out, one record per line
+    pub fn write_lines(&self, contents: &mut String) {
+        for (index, split) in self.best_splits.iter().enumerate() {
+            if let Some(ticks) = split {
+                *contents += &format!("split {} {}\n", index + 1, ticks);
+            }
+        }
+
+        if let Some(run) = &self.best_run {
+            let splits: Vec<String> = run.iter().map(|ticks| ticks.to_string()).collect();
+            *contents += &format!("run {}\n", splits.join(" "));
+        }
+
+        for attempt in &self.attempts {
+            let mut numbers = vec![(attempt.completed as u64).to_string()];
+            numbers.extend(attempt.splits.iter().map(|ticks| ticks.to_string()));
+            *contents += &format!("attempt {}\n", numbers.join(" "));
+        }
+    }
+
+    /// returns the fastest the level has been finished, if it has been
+    pub fn best_split(&self, level: u32) -> Option<u64> {
+        self.best_splits.get(level as usize - 1).copied().flatten()
+    }
+
+    /// returns the total time of the fastest full run, if there has been one
+    pub fn best_run_ticks(&self) -> Option<u64> {
+        self.best_run.as_ref().map(|run| run.iter().sum())
+    }
+
+    /// saves the time a level took if it's the fastest yet, returning if it was
+    pub fn record_split(&mut self, level: u32, ticks: u64) -> bool {
+        if level == 0 || self.best_split(level).is_some_and(|best| best <= ticks) {
+            return false;
+        }
+
+        let index = level as usize - 1;
+        if self.best_splits.len() <= index {
+            self.best_splits.resize(index + 1, None);
+        }
+
+        self.best_splits[index] = Some(ticks);
+        true
+    }
+
+    /// saves a full run's splits if it's the fastest yet, returning if it was
+    pub fn record_run(&mut self, splits: &[u64]) -> bool {
+        let ticks: u64 = splits.iter().sum();
+        if self.best_run_ticks().is_some_and(|best| best <= ticks) {
+            return false;
+        }
+
+        self.best_run = Some(splits.to_vec());
+        true
+    }
+}
+
+/// where the player was on every tick of a run through a level
+pub struct Ghost {
+    frames: Vec<RigidBody>,
+}
+
+impl Ghost {
+    pub fn new() -> Ghost {
+        Ghost { frames: Vec::new() }
+    }
+
+    /// returns the file a level's ghost is kept in
+    fn path(directory: &Path, level: u32) -> PathBuf {
+        directory.join(format!("level_{}.txt", level))
+    }
+
+    /// adds where the player is this tick to the end of the ghost
+    pub fn record(&mut self, player: &RigidBody) {
+        self.frames.push(RigidBody {
+            velocity: Vector2::new(0.0, 0.0),
+            ..*player
+        });
+    }
+
+    /// returns where the ghost is on a tick, or none
+    /// if its run had already finished by then
+    pub fn at_tick(&self, tick: u64) -> Option<RigidBody> {
+        self.frames.get(tick as usize).copied()
+    }
+
+    /// loads a level's ghost from a directory, if there is one and it can be read
+    pub fn load(directory: &Path, level: u32) -> Option<Ghost> {
+        let contents = fs::read_to_string(Ghost::path(directory, level)).ok()?;
+
+        let frames: Option<Vec<RigidBody>> = contents
+            .lines()
+            .map(|line| {
+                let numbers: Vec<f64> = line
+                    .split_whitespace()
+                    .map(|word| word.parse().ok())
+                    .collect::<Option<Vec<f64>>>()?;
+
+                match numbers[..] {
+                    [x, y, width, height] => Some(RigidBody {
+                        center: Vector2::new(x, y),
+                        width,
+                        height,
+                        velocity: Vector2::new(0.0, 0.0),
+                    }),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        frames.map(|frames| Ghost { frames })
+    }
+
+    /// saves the ghost as a level's ghost in a directory, one tick per line
+    pub fn save(&self, directory: &Path, level: u32) -> io::Result<()> {
+        let mut contents = String::new();
+
+        for frame in &self.frames {
+            contents += &format!(
+                "{} {} {} {}\n",
+                frame.center.x, frame.center.y, frame.width, frame.height
+            );
+        }
+
+        write_atomically(&Ghost::path(directory, level), &contents)
+    }
+}
@@ -0,0 +1,287 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    constants::{
+        GHOST_DIRECTORY, LEGACY_RECORDS_FILE, LEVEL_COUNT, SAVE_DIRECTORY_NAME, SAVE_FILE_NAME,
+        SAVE_HEADER, SAVE_VERSION,
+    },
+    records::{Ghost, Records},
+};
+
+/// returns the folder the game keeps its save in, inside the user's data
+/// directory for their platform (or next to the game if there isn't one)
+pub fn data_directory() -> PathBuf {
+    let home = env::var_os("HOME").map(PathBuf::from);
+
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| home.join(".local").join("share")))
+    };
+
+    match base {
+        Some(base) => base.join(SAVE_DIRECTORY_NAME),
+        None => PathBuf::from("."),
+    }
+}
+
+/// writes a file by writing a temporary file next to it and then renaming it over the
+/// old one, so the file is never left half written if the game stops partway through
+pub fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+
+    let mut file = File::create(&temporary)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+
+    fs::rename(&temporary, path)
+}
+
+/// how the player has done in a level
+#[derive(Clone, Default)]
+pub struct LevelProgress {
+    /// if the player has reached the level's goal
+    pub completed: bool,
+    /// how many times the player has died in the level
+    pub deaths: u64,
+    /// which of the level's gems the player has finished the level with
+    pub gems: Vec<bool>,
+}
+
+/// options the player can change from the settings menu
+#[derive(Clone, Copy)]
+pub struct Settings {
+    /// if levels are played as time trials (this is always on with the time trial argument)
+    pub time_trials: bool,
+    /// if time trials show the ghost of each level's fastest time
+    pub race_ghosts: bool,
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Settings {
+            time_trials: false,
+            race_ghosts: true,
+        }
+    }
+}
+
+/// everything kept between launches of the game
+pub struct SaveData {
+    /// the furthest level the player can play
+    pub unlocked_level: u32,
+    /// how the player has done in each level (index 0 is level 1)
+    levels: Vec<LevelProgress>,
+    /// the player's time trial personal bests and history
+    pub records: Records,
+    /// what the player picked in the settings menu
+    pub settings: Settings,
+    /// the folder the save and ghosts are kept in
+    directory: PathBuf,
+}
+
+impl SaveData {
+    pub fn new(directory: PathBuf) -> SaveData {
+        SaveData {
+            unlocked_level: 1,
+            levels: vec![LevelProgress::default(); LEVEL_COUNT as usize],
+            records: Records::new(),
+            settings: Settings::new(),
+            directory,
+        }
+    }
+
+    /// returns the folder level ghosts are kept in
+    pub fn ghost_directory(&self) -> PathBuf {
+        self.directory.join(GHOST_DIRECTORY)
+    }
+
+    /// returns how the player has done in a level
+    pub fn progress(&self, level: u32) -> &LevelProgress {
+        &self.levels[level as usize - 1]
+    }
+
+    /// returns how the player has done in a level, to be changed
+    pub fn progress_mut(&mut self, level: u32) -> &mut LevelProgress {
+        &mut self.levels[level as usize - 1]
+    }
+
+    /// marks a level as completed with the gems the player had when they
+    /// finished it (on top of any from before), and unlocks the next level
+    pub fn complete_level(&mut self, level: u32, gems: &[bool]) {
+        let progress = self.progress_mut(level);
+        progress.completed = true;
+
+        if progress.gems.len() < gems.len() {
+            progress.gems.resize(gems.len(), false);
+        }
+        for (saved, collected) in progress.gems.iter_mut().zip(gems) {
+            *saved |= collected;
+        }
+
+        self.unlocked_level = self.unlocked_level.max(u32::min(level + 1, LEVEL_COUNT));
+    }
+
+    /**
+    loads the save from the user's data directory
+    * a missing save starts a new one, bringing over
+      records kept from before there was a save file
+    * a save that can't be read (including ones from newer versions
+      of the game) is backed up and replaced with a new one
+    */
+    pub fn load() -> SaveData {
+        let directory = data_directory();
+        let path = directory.join(SAVE_FILE_NAME);
+
+        let problem = match fs::read_to_string(&path) {
+            Ok(contents) => match SaveData::parse(directory.clone(), &contents) {
+                Ok(save) => return save,
+                Err(problem) => problem,
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return SaveData::migrate_legacy(directory);
+            }
+            Err(error) => error.to_string(),
+        };
+
+        // keep the old save around in case it can be recovered by hand
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        eprintln!(
+            "Save file couldn't be read ({}), starting a new one (the old one is at {})",
+            problem,
+            Path::new(&backup).display()
+        );
+
+        if let Err(error) = fs::rename(&path, &backup) {
+            eprintln!("Error backing up save: {}", error);
+        }
+
+        SaveData::new(directory)
+    }
+
+    // reads the contents of a save file, returning what's wrong with it if it can't be read
+    fn parse(directory: PathBuf, contents: &str) -> Result<SaveData, String> {
+        let mut lines = contents.lines();
+
+        let version: u32 = lines
+            .next()
+            .and_then(|header| header.strip_prefix(SAVE_HEADER))
+            .and_then(|version| version.trim().parse().ok())
+            .ok_or("it's missing its header")?;
+
+        // older formats get migrated here whenever the format changes
+        match version {
+            SAVE_VERSION => (),
+            // version 1 saves are the same, just without settings, which are left as the defaults
+            1 => (),
+            _ => return Err(format!("it's from an unknown version ({})", version)),
+        }
+
+        let mut save = SaveData::new(directory);
+
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let mut words = line.split_whitespace();
+            let kind = words.next();
+            let numbers: Option<Vec<u64>> = words.map(|word| word.parse().ok()).collect();
+
+            match (kind, numbers.as_deref()) {
+                (Some("time_trials"), Some([on])) => save.settings.time_trials = *on == 1,
+                (Some("race_ghosts"), Some([on])) => save.settings.race_ghosts = *on == 1,
+                (Some("unlocked"), Some([level])) => {
+                    save.unlocked_level = (*level).clamp(1, LEVEL_COUNT as u64) as u32;
+                }
+                (Some("level"), Some([level, completed, deaths, gems @ ..])) => {
+                    // levels past the last one are ignored
+                    if *level == 0 {
+                        return Err(format!("it has a bad line ({})", line));
+                    } else if *level <= LEVEL_COUNT as u64 {
+                        *save.progress_mut(*level as u32) = LevelProgress {
+                            completed: *completed == 1,
+                            deaths: *deaths,
+                            gems: gems.iter().map(|gem| *gem == 1).collect(),
+                        };
+                    }
+                }
+                _ => {
+                    if !save.records.read_line(line) {
+                        return Err(format!("it has a bad line ({})", line));
+                    }
+                }
+            }
+        }
+
+        Ok(save)
+    }
+
+    // starts a new save with the records and ghosts that used to be kept next to the game
+    fn migrate_legacy(directory: PathBuf) -> SaveData {
+        let mut save = SaveData::new(directory);
+
+        let Ok(contents) = fs::read_to_string(LEGACY_RECORDS_FILE) else {
+            return save;
+        };
+
+        for line in contents.lines() {
+            save.records.read_line(line);
+        }
+
+        // any level with a time has been beaten
+        for level in 1..=LEVEL_COUNT {
+            if save.records.best_split(level).is_some() {
+                save.complete_level(level, &[]);
+            }
+
+            if let Some(ghost) = Ghost::load(Path::new(GHOST_DIRECTORY), level) {
+                ghost
+                    .save(&save.ghost_directory(), level)
+                    .unwrap_or_else(|error| {
+                        eprintln!("Error moving ghost: {}", error);
+                    });
+            }
+        }
+
+        save.save().unwrap_or_else(|error| {
+            eprintln!("Error saving: {}", error);
+        });
+
+        save
+    }
+
+    /// writes the save to the user's data directory, one thing per line
+    pub fn save(&self) -> io::Result<()> {
+        let mut contents = format!("{} {}\n", SAVE_HEADER, SAVE_VERSION);
+        contents += &format!("time_trials {}\n", self.settings.time_trials as u8);
+        contents += &format!("race_ghosts {}\n", self.settings.race_ghosts as u8);
+        contents += &format!("unlocked {}\n", self.unlocked_level);
+
+        for (index, progress) in self.levels.iter().enumerate() {
+            let mut numbers = vec![
+                (index + 1).to_string(),
+                (progress.completed as u64).to_string(),
+                progress.deaths.to_string(),
+            ];
+            numbers.extend(progress.gems.iter().map(|gem| (*gem as u64).to_string()));
+            contents += &format!("level {}\n", numbers.join(" "));
+        }
+
+        self.records.write_lines(&mut contents);
+
+        write_atomically(&self.directory.join(SAVE_FILE_NAME), &contents)
+    }
+}
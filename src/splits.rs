@@ -0,0 +1,328 @@
+use std::{fs, io};
+
+use super::{
+    constants::{
+        LEVEL_COUNT, SIMULATION_TICK_HUNDREDTH_SECONDS, SPLITS_CATEGORY_NAME, SPLITS_GAME_NAME,
+    },
+    records::{Attempt, Records},
+};
+
+// formats a number of ticks as a splitting tool time (hours:minutes:seconds),
+// keeping seven decimal places so the time reads back as the same number of ticks
+fn format_time(ticks: u64) -> String {
+    let seconds = ticks as f64 * SIMULATION_TICK_HUNDREDTH_SECONDS / 100.0;
+    let ten_millionths = (seconds * 10_000_000.0).round() as u64;
+
+    format!(
+        "{:02}:{:02}:{:02}.{:07}",
+        ten_millionths / 36_000_000_000,
+        ten_millionths / 600_000_000 % 60,
+        ten_millionths / 10_000_000 % 60,
+        ten_millionths % 10_000_000
+    )
+}
+
+// reads a splitting tool time ([days.]hours:minutes:seconds) as a number of ticks
+fn parse_time(text: &str) -> Option<u64> {
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    let [hours, minutes, seconds] = parts[..] else {
+        return None;
+    };
+
+    let hours: f64 = match hours.split_once('.') {
+        Some((days, hours)) => days.parse::<f64>().ok()? * 24.0 + hours.parse::<f64>().ok()?,
+        None => hours.parse().ok()?,
+    };
+    let seconds =
+        hours * 3600.0 + minutes.parse::<f64>().ok()? * 60.0 + seconds.parse::<f64>().ok()?;
+
+    if seconds < 0.0 {
+        return None;
+    }
+
+    Some((seconds * 100.0 / SIMULATION_TICK_HUNDREDTH_SECONDS).round() as u64)
+}
+
+// finds every element with the given tag, returning the attributes
+// and contents of each (this is just enough xml for splits files)
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<(&'a str, &'a str)> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+
+    let mut found = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+
+        // make sure this isn't just a tag that starts the same way
+        if !rest.starts_with([' ', '>', '/']) {
+            continue;
+        }
+
+        let Some(tag_end) = rest.find('>') else {
+            break;
+        };
+        let attributes = &rest[..tag_end];
+        rest = &rest[tag_end + 1..];
+
+        // self-closing elements don't have any contents
+        if let Some(attributes) = attributes.strip_suffix('/') {
+            found.push((attributes, ""));
+            continue;
+        }
+
+        let Some(content_end) = rest.find(&close) else {
+            break;
+        };
+        found.push((attributes, &rest[..content_end]));
+        rest = &rest[content_end + close.len()..];
+    }
+
+    found
+}
+
+// returns the value of an attribute in an element's attributes
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!("{}=\"", name))? + name.len() + 2;
+    let length = attributes[start..].find('"')?;
+
+    Some(&attributes[start..start + length])
+}
+
+// returns the time inside an element, preferring real time over game time
+fn time_of(xml: &str) -> Option<u64> {
+    elements(xml, "RealTime")
+        .into_iter()
+        .chain(elements(xml, "GameTime"))
+        .find_map(|(_, time)| parse_time(time))
+}
+
+// returns an element holding a time, or an empty one if there's no time
+fn time_element(tag: &str, attributes: &str, ticks: Option<u64>) -> String {
+    match ticks {
+        Some(ticks) => format!(
+            "<{0}{1}><RealTime>{2}</RealTime></{0}>",
+            tag,
+            attributes,
+            format_time(ticks)
+        ),
+        None => format!("<{}{} />", tag, attributes),
+    }
+}
+
+/**
+writes the time trial records to a splits file in the xml format common splitting tools use
+* each level is a segment, with the personal best's splits,
+  the best time for the level and the time of every attempt
+*/
+pub fn export(records: &Records, path: &str) -> io::Result<()> {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml += "<Run version=\"1.7.0\">\n";
+    xml += "  <GameIcon />\n";
+    xml += &format!("  <GameName>{}</GameName>\n", SPLITS_GAME_NAME);
+    xml += &format!("  <CategoryName>{}</CategoryName>\n", SPLITS_CATEGORY_NAME);
+    xml += "  <Offset>00:00:00</Offset>\n";
+    xml += &format!(
+        "  <AttemptCount>{}</AttemptCount>\n",
+        records.attempts.len()
+    );
+
+    // finished attempts have the time the whole run took
+    xml += "  <AttemptHistory>\n";
+    for (index, attempt) in records.attempts.iter().enumerate() {
+        let total = attempt.completed.then(|| attempt.splits.iter().sum());
+        let attributes = format!(" id=\"{}\"", index + 1);
+        xml += &format!("    {}\n", time_element("Attempt", &attributes, total));
+    }
+    xml += "  </AttemptHistory>\n";
+
+    xml += "  <Segments>\n";
+    for level in 1..=LEVEL_COUNT {
+        let index = level as usize - 1;
+
+        // personal best split times are from the start of the run
+        let personal_best = records
+            .best_run
+            .as_ref()
+            .and_then(|run| run.get(..=index))
+            .map(|splits| splits.iter().sum());
+
+        xml += "    <Segment>\n";
+        xml += &format!("      <Name>Level {}</Name>\n", level);
+        xml += "      <Icon />\n";
+        xml += "      <SplitTimes>\n";
+        xml += &format!(
+            "        {}\n",
+            time_element("SplitTime", " name=\"Personal Best\"", personal_best)
+        );
+        xml += "      </SplitTimes>\n";
+        xml += &format!(
+            "      {}\n",
+            time_element("BestSegmentTime", "", records.best_split(level))
+        );
+
+        xml += "      <SegmentHistory>\n";
+        for (id, attempt) in records.attempts.iter().enumerate() {
+            if let Some(ticks) = attempt.splits.get(index) {
+                let attributes = format!(" id=\"{}\"", id + 1);
+                xml += &format!(
+                    "        {}\n",
+                    time_element("Time", &attributes, Some(*ticks))
+                );
+            }
+        }
+        xml += "      </SegmentHistory>\n";
+        xml += "    </Segment>\n";
+    }
+    xml += "  </Segments>\n";
+
+    xml += "  <AutoSplitterSettings />\n";
+    xml += "</Run>\n";
+
+    fs::write(path, xml)
+}
+
+/**
+seeds the time trial records from a splits file
+* best level times and the personal best are only kept if they're faster
+* the personal best and completed attempts need a segment for every level
+* attempts are only read if there aren't any yet, so importing
+  a file that was exported from here doesn't count them twice
+*/
+pub fn import(records: &mut Records, path: &str) -> io::Result<()> {
+    let xml = fs::read_to_string(path)?;
+
+    let segments: Vec<&str> = elements(&xml, "Segment")
+        .into_iter()
+        .map(|(_, segment)| segment)
+        .collect();
+
+    if segments.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "splits file has no segments",
+        ));
+    }
+
+    // the splits only line up with a whole run of the game if there's a segment for each level
+    let full_run = segments.len() == LEVEL_COUNT as usize;
+
+    // the best time for each level (segments past the last level don't match one)
+    for (index, segment) in segments.iter().take(LEVEL_COUNT as usize).enumerate() {
+        let gold = elements(segment, "BestSegmentTime")
+            .first()
+            .and_then(|(_, time)| time_of(time));
+
+        if let Some(ticks) = gold {
+            records.record_split(index as u32 + 1, ticks);
+        }
+    }
+
+    // the personal best, which is stored as times from the start of the run
+    let personal_best: Option<Vec<u64>> = segments
+        .iter()
+        .map(|segment| {
+            elements(segment, "SplitTime")
+                .into_iter()
+                .find(|(attributes, _)| attribute(attributes, "name") == Some("Personal Best"))
+                .and_then(|(_, time)| time_of(time))
+        })
+        .collect();
+
+    if let (true, Some(cumulative)) = (full_run, personal_best) {
+        let splits: Vec<u64> = cumulative
+            .iter()
+            .enumerate()
+            .map(|(index, ticks)| match index {
+                0 => *ticks,
+                _ => ticks.saturating_sub(cumulative[index - 1]),
+            })
+            .collect();
+
+        records.record_run(&splits);
+    }
+
+    // each attempt got as far as the segments that have a time with its id
+    if records.attempts.is_empty() {
+        for (attributes, time) in elements(&xml, "Attempt") {
+            let Some(id) = attribute(attributes, "id") else {
+                continue;
+            };
+
+            let splits: Vec<u64> = segments
+                .iter()
+                .map_while(|segment| {
+                    elements(segment, "Time")
+                        .into_iter()
+                        .find(|(attributes, _)| attribute(attributes, "id") == Some(id))
+                        .and_then(|(_, time)| time_of(time))
+                })
+                .collect();
+
+            if splits.is_empty() {
+                continue;
+            }
+
+            records.attempts.push(Attempt {
+                completed: full_run && time_of(time).is_some() && splits.len() == segments.len(),
+                splits,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // exports records and imports them into other records
+    fn export_then_import(records: &Records, into: &mut Records) {
+        let path = std::env::temp_dir().join(format!("splits_test_{}.lss", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        export(records, path).unwrap();
+        let result = import(into, path);
+        fs::remove_file(path).unwrap();
+        result.unwrap();
+    }
+
+    #[test]
+    fn times_read_back_as_the_same_ticks() {
+        for ticks in (0..100_000).chain([1_000_000, 10_000_000, 1_000_000_000]) {
+            assert_eq!(parse_time(&format_time(ticks)), Some(ticks), "{}", ticks);
+        }
+    }
+
+    #[test]
+    fn exported_records_import_unchanged() {
+        let run: Vec<u64> = (1..=LEVEL_COUNT as u64).map(|level| level * 1001).collect();
+
+        let mut records = Records::new();
+        records.record_run(&run);
+        for (index, ticks) in run.iter().enumerate() {
+            records.record_split(index as u32 + 1, ticks - 1);
+        }
+        records.attempts.push(Attempt {
+            splits: run.clone(),
+            completed: true,
+        });
+
+        let mut imported = Records::new();
+        export_then_import(&records, &mut imported);
+
+        assert!(imported.best_run == records.best_run);
+        assert!(imported.best_splits == records.best_splits);
+        assert_eq!(imported.attempts.len(), 1);
+        assert!(imported.attempts[0].splits == run);
+        assert!(imported.attempts[0].completed);
+
+        // importing our own export again can't beat any of the times in it
+        assert!(!imported.record_run(&run));
+        export_then_import(&imported, &mut records);
+        assert!(records.best_run == imported.best_run);
+        assert!(records.best_splits == imported.best_splits);
+    }
+}